@@ -1,9 +1,16 @@
 use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
-use marsupial::{KT128, KT256};
+use marsupial::{Hasher, KT128, KT256};
 use rand::prelude::*;
 
 const KIB: usize = 1024;
 
+// Groups benchmarking marsupial are labeled with the XKCP/K12 implementation
+// it was built with, so that e.g. a default build and a `force-portable` one
+// can be compared directly
+fn group_name(name: &str) -> String {
+    format!("{} ({})", name, marsupial::active_implementation())
+}
+
 // This struct randomizes two things:
 // 1. The actual bytes of input.
 // 2. The page offset the input starts at.
@@ -41,7 +48,7 @@ impl RandomInput {
 }
 
 fn bench_blake3(c: &mut Criterion) {
-    let mut g = c.benchmark_group("BLAKE3");
+    let mut g = c.benchmark_group(group_name("BLAKE3"));
 
     for n in [1, 2, 4, 8, 16, 32, 64, 128, 256, 512, 1024].iter() {
         let bytes = n * KIB;
@@ -65,7 +72,7 @@ fn bench_blake3(c: &mut Criterion) {
 }
 
 fn bench_kt128(c: &mut Criterion) {
-    let mut g = c.benchmark_group("KT128");
+    let mut g = c.benchmark_group(group_name("KT128"));
 
     for n in [1, 2, 4, 8, 16, 32, 64, 128, 256, 512, 1024].iter() {
         let bytes = n * KIB;
@@ -110,7 +117,7 @@ fn bench_kt128(c: &mut Criterion) {
 }
 
 fn bench_kt256(c: &mut Criterion) {
-    let mut g = c.benchmark_group("KT256");
+    let mut g = c.benchmark_group(group_name("KT256"));
 
     for n in [1, 2, 4, 8, 16, 32, 64, 128, 256, 512, 1024].iter() {
         let bytes = n * KIB;
@@ -123,5 +130,177 @@ fn bench_kt256(c: &mut Criterion) {
     }
 }
 
-criterion_group!(benches, bench_kt128, bench_kt256, bench_blake3);
+// The sweeps above start at 1 KiB, where the fixed cost of each hash (setting
+// up and finalizing the state) is already amortized. This measures it on
+// inputs short enough that it dominates, in hashes per second
+fn bench_tiny_inputs(c: &mut Criterion) {
+    let mut g = c.benchmark_group(group_name("tiny inputs"));
+    g.throughput(Throughput::Elements(1));
+
+    for n in [1, 8, 16, 32, 64, 128, 256].iter() {
+        let mut marsupial_input_kt128 = black_box(RandomInput::new(*n));
+        g.bench_function(BenchmarkId::new("marsupial-kt128", n), |b| {
+            b.iter(|| marsupial::hash::<KT128>(marsupial_input_kt128.get()))
+        });
+
+        let mut marsupial_input_kt256 = black_box(RandomInput::new(*n));
+        g.bench_function(BenchmarkId::new("marsupial-kt256", n), |b| {
+            b.iter(|| marsupial::hash::<KT256>(marsupial_input_kt256.get()))
+        });
+
+        let mut k12_input = black_box(RandomInput::new(*n));
+        g.bench_function(BenchmarkId::new("k12", n), |b| {
+            b.iter(|| {
+                use digest::{ExtendableOutput, Update, XofReader};
+                use k12::{KangarooTwelve, KangarooTwelveCore};
+
+                let mut state = KangarooTwelve::from_core(KangarooTwelveCore::default());
+                state.update(k12_input.get());
+
+                let mut reader = state.finalize_xof();
+                let mut output = [0; 32];
+                reader.read(&mut output);
+                output
+            })
+        });
+
+        let mut tk_input = black_box(RandomInput::new(*n));
+        g.bench_function(BenchmarkId::new("tiny-keccak", n), |b| {
+            b.iter(|| {
+                use tiny_keccak::{Hasher, IntoXof, KangarooTwelve, Xof};
+
+                let mut state = KangarooTwelve::new(b"");
+                state.update(tk_input.get());
+
+                let mut xof = state.into_xof();
+                let mut output = [0; 32];
+                xof.squeeze(&mut output);
+                output
+            })
+        });
+    }
+}
+
+// XKCP/K12 only uses its parallel permutations for chunks passed to it in the
+// same call, so this compares a single `update` against splitting the same
+// input into smaller ones
+fn bench_update_granularity(c: &mut Criterion) {
+    let mut g = c.benchmark_group(group_name("Hasher::update granularity"));
+
+    let bytes = 4 * KIB * KIB;
+    g.throughput(Throughput::Bytes(bytes as u64));
+
+    let mut input = black_box(RandomInput::new(bytes));
+    for n in [1, 8, 64, 4 * KIB].iter() {
+        g.bench_function(
+            BenchmarkId::new("marsupial-kt128", format!("{}KiB", n)),
+            |b| {
+                b.iter(|| {
+                    // the largest size passes the whole input in one call
+                    let mut hasher = Hasher::<KT128>::new();
+                    for piece in input.get().chunks(n * KIB) {
+                        hasher.update(piece);
+                    }
+                    hasher.finalize()
+                })
+            },
+        );
+    }
+}
+
+// Hashing many small messages is dominated by the fixed cost of each hash,
+// which this compares between constructing a new `Hasher` per message and
+// copying one that was constructed ahead of time
+fn bench_small_messages(c: &mut Criterion) {
+    let mut g = c.benchmark_group(group_name("small messages"));
+    g.throughput(Throughput::Elements(1));
+
+    let mut input = black_box(RandomInput::new(64));
+    g.bench_function("marsupial-kt128-new", |b| {
+        b.iter(|| {
+            let mut hasher = Hasher::<KT128>::new();
+            hasher.update(input.get());
+            hasher.finalize()
+        })
+    });
+
+    let fresh = Hasher::<KT128>::new();
+    g.bench_function("marsupial-kt128-clone", |b| {
+        b.iter(|| {
+            let mut hasher = black_box(&fresh).clone();
+            hasher.update(input.get());
+            hasher.finalize()
+        })
+    });
+}
+
+fn bench_output_reader(c: &mut Criterion) {
+    let mut g = c.benchmark_group(group_name("OutputReader"));
+
+    for n in [1, 2, 4, 8, 16, 32, 64, 128, 256, 512, 1024].iter() {
+        let bytes = n * KIB;
+        g.throughput(Throughput::Bytes(bytes as u64));
+
+        let mut output = vec![0; bytes];
+
+        let mut hasher = Hasher::<KT128>::new();
+        hasher.update(RandomInput::new(KIB).get());
+        let mut reader_kt128 = hasher.finalize_xof();
+        g.bench_function(BenchmarkId::new("marsupial-kt128", n), |b| {
+            b.iter(|| reader_kt128.squeeze(black_box(&mut output)))
+        });
+
+        let mut hasher = Hasher::<KT256>::new();
+        hasher.update(RandomInput::new(KIB).get());
+        let mut reader_kt256 = hasher.finalize_xof();
+        g.bench_function(BenchmarkId::new("marsupial-kt256", n), |b| {
+            b.iter(|| reader_kt256.squeeze(black_box(&mut output)))
+        });
+    }
+}
+
+fn bench_discard(c: &mut Criterion) {
+    let mut g = c.benchmark_group(group_name("OutputReader::discard"));
+
+    for n in [1, 4, 16, 64, 256, 1024].iter() {
+        let bytes = n * KIB;
+        g.throughput(Throughput::Bytes(bytes as u64));
+
+        let mut hasher = Hasher::<KT128>::new();
+        hasher.update(RandomInput::new(KIB).get());
+        let mut reader = hasher.finalize_xof();
+        g.bench_function(BenchmarkId::new("marsupial-kt128", n), |b| {
+            b.iter(|| reader.discard(black_box(bytes as u64)))
+        });
+    }
+}
+
+fn bench_hash_eq(c: &mut Criterion) {
+    let mut g = c.benchmark_group(group_name("Hash comparison"));
+    g.throughput(Throughput::Elements(1));
+
+    let mut input = RandomInput::new(KIB);
+    let a = marsupial::hash::<KT128>(input.get());
+    let b = marsupial::Hash::from(*a.as_bytes());
+
+    g.bench_function("ct_eq", |bencher| {
+        bencher.iter(|| black_box(&a).ct_eq(black_box(&b)))
+    });
+    g.bench_function("fast_eq", |bencher| {
+        bencher.iter(|| black_box(&a).fast_eq(black_box(&b)))
+    });
+}
+
+criterion_group!(
+    benches,
+    bench_kt128,
+    bench_kt256,
+    bench_tiny_inputs,
+    bench_blake3,
+    bench_update_granularity,
+    bench_small_messages,
+    bench_output_reader,
+    bench_discard,
+    bench_hash_eq
+);
 criterion_main!(benches);
@@ -0,0 +1,219 @@
+//! Configuration of hash states in one place
+
+use crate::{Hasher, OutputReader, SecurityLevel};
+#[cfg(feature = "alloc")]
+use alloc::{vec, vec::Vec};
+use core::{fmt, marker::PhantomData};
+
+/// A builder for [`ConfiguredHasher`]s, collecting the parameters that would
+/// otherwise be passed to [`Hasher`]'s constructors and finalization methods
+///
+/// By default, the customization string is empty and the output is as long
+/// as the canonical [`struct@Hash`](crate::Hash), so a [`ConfiguredHasher`]
+/// built without changing anything produces the same bytes as
+/// [`Hasher::finalize`]
+///
+/// The `N` parameter indicates the security strength level in number of bits.
+/// Valid values for it are:
+///
+/// - [`KT128`](crate::KT128)
+/// - [`KT256`](crate::KT256)
+///
+/// Any other value will fail to compile
+///
+/// # Examples
+///
+/// ```
+/// # use marsupial::{Hasher, HasherBuilder, KT128};
+/// let mut hasher = HasherBuilder::<KT128>::new()
+///     .customization(b"ctx")
+///     .output_len(48)
+///     .build();
+/// hasher.update(b"foobarbaz");
+///
+/// let mut output = [0; 48];
+/// hasher.finalize_into(&mut output);
+///
+/// let mut expected = [0; 48];
+/// let mut reference = Hasher::<KT128>::new();
+/// reference.update(b"foobarbaz");
+/// reference.finalize_custom_xof(b"ctx").squeeze(&mut expected);
+/// assert_eq!(output, expected);
+/// ```
+pub struct HasherBuilder<'a, N> {
+    customization: &'a [u8],
+    output_len: usize,
+    capacity_hint: usize,
+    _security_level: PhantomData<N>,
+}
+
+impl<'a, N> HasherBuilder<'a, N>
+where
+    N: SecurityLevel,
+{
+    /// Construct a new [`HasherBuilder`] with the default parameters
+    pub fn new() -> Self {
+        Self {
+            customization: &[],
+            output_len: N::HASH_ARRAY_LENGTH,
+            capacity_hint: 0,
+            _security_level: PhantomData,
+        }
+    }
+
+    /// Set the customization string passed to XKCP/K12 on finalization
+    pub fn customization(mut self, customization: &'a [u8]) -> Self {
+        self.customization = customization;
+        self
+    }
+
+    /// Set the number of output bytes produced by
+    /// [`ConfiguredHasher::finalize`] and
+    /// [`ConfiguredHasher::finalize_into`]
+    pub fn output_len(mut self, output_len: usize) -> Self {
+        self.output_len = output_len;
+        self
+    }
+
+    /// Set the hint of how many bytes of input the [`ConfiguredHasher`] will
+    /// be fed. See [`Hasher::with_capacity_hint`]
+    pub fn capacity_hint(mut self, capacity_hint: usize) -> Self {
+        self.capacity_hint = capacity_hint;
+        self
+    }
+
+    /// Construct a [`ConfiguredHasher`] with the parameters set so far. The
+    /// [`HasherBuilder`] isn't consumed, so it can build any number of them
+    pub fn build(&self) -> ConfiguredHasher<'a, N> {
+        ConfiguredHasher {
+            hasher: Hasher::with_capacity_hint(self.capacity_hint),
+            customization: self.customization,
+            output_len: self.output_len,
+        }
+    }
+}
+
+impl<N> Clone for HasherBuilder<'_, N>
+where
+    N: SecurityLevel,
+{
+    fn clone(&self) -> Self {
+        Self {
+            customization: self.customization,
+            output_len: self.output_len,
+            capacity_hint: self.capacity_hint,
+            _security_level: PhantomData,
+        }
+    }
+}
+
+impl<N> Default for HasherBuilder<'_, N>
+where
+    N: SecurityLevel,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<N> fmt::Debug for HasherBuilder<'_, N>
+where
+    N: SecurityLevel,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HasherBuilder")
+            .field("output_len", &self.output_len)
+            .field("capacity_hint", &self.capacity_hint)
+            .finish_non_exhaustive()
+    }
+}
+
+/// An incremental hash state that carries the parameters it'll be finalized
+/// with, as set on the [`HasherBuilder`] that built it
+pub struct ConfiguredHasher<'a, N> {
+    hasher: Hasher<N>,
+    customization: &'a [u8],
+    output_len: usize,
+}
+
+impl<N> ConfiguredHasher<'_, N>
+where
+    N: SecurityLevel,
+{
+    /// Add input bytes to the hash state. You can call this any number of
+    /// times, until the [`ConfiguredHasher`] is finalized
+    pub fn update(&mut self, input: impl AsRef<[u8]>) -> &mut Self {
+        self.hasher.update(input);
+        self
+    }
+
+    /// The number of output bytes produced by [`finalize`](#method.finalize)
+    /// and [`finalize_into`](#method.finalize_into)
+    #[inline]
+    pub fn output_len(&self) -> usize {
+        self.output_len
+    }
+
+    /// Finalize the hash state, consuming the [`ConfiguredHasher`], and return
+    /// the configured number of output bytes
+    #[cfg(feature = "alloc")]
+    #[must_use]
+    pub fn finalize(self) -> Vec<u8> {
+        let mut output = vec![0; self.output_len];
+        self.finalize_into(&mut output);
+        output
+    }
+
+    /// Finalize the hash state, consuming the [`ConfiguredHasher`], and write
+    /// the configured number of output bytes to `out`
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of `out` isn't the configured output length
+    pub fn finalize_into(self, out: &mut [u8]) {
+        assert_eq!(
+            out.len(),
+            self.output_len,
+            "the output buffer doesn't match the configured output length"
+        );
+        self.finalize_xof().squeeze(out);
+    }
+
+    /// Finalize the hash state, consuming the [`ConfiguredHasher`] and
+    /// returning an [`OutputReader`], which can supply any number of output
+    /// bytes regardless of the configured output length
+    #[must_use]
+    pub fn finalize_xof(self) -> OutputReader {
+        self.hasher.finalize_custom_xof(self.customization)
+    }
+
+    /// Split off the underlying [`Hasher`], discarding the finalization
+    /// parameters
+    pub fn into_inner(self) -> Hasher<N> {
+        self.hasher
+    }
+}
+
+impl<N> Clone for ConfiguredHasher<'_, N>
+where
+    N: SecurityLevel,
+{
+    fn clone(&self) -> Self {
+        Self {
+            hasher: self.hasher.clone(),
+            customization: self.customization,
+            output_len: self.output_len,
+        }
+    }
+}
+
+impl<N> fmt::Debug for ConfiguredHasher<'_, N>
+where
+    N: SecurityLevel,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ConfiguredHasher")
+            .field("output_len", &self.output_len)
+            .finish_non_exhaustive()
+    }
+}
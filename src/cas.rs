@@ -0,0 +1,154 @@
+//! Keys for content-addressed storage
+//!
+//! A [`ContentId`] identifies a piece of content by its KT128 hash, and can be
+//! rendered as a string that's safe to use as a file name
+//!
+//! # Examples
+//!
+//! ```
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! # use marsupial::cas::ContentId;
+//! # #[cfg(feature = "alloc")] {
+//! let id = ContentId::from_content(b"foobarbaz");
+//! let name = id.to_base32();
+//! assert_eq!(name.parse::<ContentId>()?, id);
+//! # }
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::{error::ContentIdParseError, hash, Hash, KT128};
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+use core::{fmt, str::FromStr};
+
+/// The RFC 4648 base32 alphabet, lowercased
+#[cfg(feature = "alloc")]
+const BASE32_ALPHABET: &[u8; 32] = b"abcdefghijklmnopqrstuvwxyz234567";
+
+/// The length of a [`ContentId`] encoded in base32 (in characters)
+pub const BASE32_LEN: usize = (32 * 8usize).div_ceil(5);
+
+/// An identifier for a piece of content, consisting of its KT128
+/// [`struct@Hash`]
+///
+/// Content ids are public by nature, so unlike [`struct@Hash`], the
+/// [`Debug`](fmt::Debug) implementation shows the hash, and the
+/// [`Display`](fmt::Display) implementation renders it as lowercase
+/// hexadecimal
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ContentId(Hash<32>);
+
+impl ContentId {
+    /// Construct the [`ContentId`] of a piece of content by hashing it. This
+    /// is equivalent to `ContentId::from(marsupial::hash::<KT128>(content))`
+    #[must_use]
+    pub fn from_content(content: &[u8]) -> Self {
+        Self(hash::<KT128>(content))
+    }
+
+    /// The [`struct@Hash`] of the content
+    #[inline]
+    pub fn hash(&self) -> &Hash<32> {
+        &self.0
+    }
+
+    /// Encode the [`ContentId`] in unpadded, lowercase base32 (as defined in
+    /// RFC 4648), which only uses characters that are safe in file names on
+    /// case-insensitive filesystems. The result is always
+    /// [`BASE32_LEN`] characters long, and can be parsed back with
+    /// [`from_base32`](#method.from_base32) or [`FromStr`]
+    #[cfg(feature = "alloc")]
+    pub fn to_base32(&self) -> String {
+        let mut encoded = String::with_capacity(BASE32_LEN);
+        let mut buffer = 0u16;
+        let mut bits = 0;
+        for byte in self.0.as_bytes() {
+            buffer = (buffer << 8) | *byte as u16;
+            bits += 8;
+            while bits >= 5 {
+                bits -= 5;
+                encoded.push(BASE32_ALPHABET[((buffer >> bits) & 0x1f) as usize] as char);
+            }
+        }
+        if bits > 0 {
+            encoded.push(BASE32_ALPHABET[((buffer << (5 - bits)) & 0x1f) as usize] as char);
+        }
+        encoded
+    }
+
+    /// Decode a [`ContentId`] from the encoding produced by
+    /// [`to_base32`](#method.to_base32). Uppercase characters are accepted as
+    /// well
+    pub fn from_base32(encoded: &str) -> Result<Self, ContentIdParseError> {
+        if encoded.len() != BASE32_LEN {
+            return Err(ContentIdParseError::InvalidLength(encoded.len()));
+        }
+
+        let mut bytes = [0; 32];
+        let mut buffer = 0u16;
+        let mut bits = 0;
+        let mut i = 0;
+        for character in encoded.chars() {
+            let value = match character {
+                'a'..='z' => character as u16 - 'a' as u16,
+                'A'..='Z' => character as u16 - 'A' as u16,
+                '2'..='7' => character as u16 - '2' as u16 + 26,
+                _ => return Err(ContentIdParseError::InvalidCharacter(character)),
+            };
+            buffer = (buffer << 5) | value;
+            bits += 5;
+            if bits >= 8 {
+                bits -= 8;
+                bytes[i] = (buffer >> bits) as u8;
+                i += 1;
+            }
+        }
+
+        //NOTE: the leftover bits are padding, which must be zero for there to
+        //      be exactly one encoding of each id
+        if buffer & ((1 << bits) - 1) != 0 {
+            return Err(ContentIdParseError::NonCanonical);
+        }
+        Ok(Self(Hash::from(bytes)))
+    }
+}
+
+impl From<Hash<32>> for ContentId {
+    #[inline]
+    fn from(hash: Hash<32>) -> Self {
+        Self(hash)
+    }
+}
+
+impl From<ContentId> for Hash<32> {
+    #[inline]
+    fn from(id: ContentId) -> Self {
+        id.0
+    }
+}
+
+impl FromStr for ContentId {
+    type Err = ContentIdParseError;
+
+    /// Equivalent to [`ContentId::from_base32`]
+    #[inline]
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_base32(s)
+    }
+}
+
+impl fmt::Display for ContentId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for byte in self.0.as_bytes() {
+            write!(f, "{:02x}", byte)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for ContentId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "ContentId({})", self)
+    }
+}
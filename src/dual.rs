@@ -0,0 +1,94 @@
+//! Hashing the same input with KangarooTwelve and another hash function
+
+use crate::{Hasher, SecurityLevel};
+use core::fmt;
+
+/// An incremental hash state that feeds every write to both a [`Hasher`] and
+/// another hash function implementing [`digest::Update`], in a single pass
+/// over the input
+///
+/// This is meant for migrating to KangarooTwelve from another hash function,
+/// such as SHAKE128, when both digests need to be computed (e.g. to compare
+/// them, or to write both) for a while
+///
+/// The `N` parameter indicates the security strength level in number of bits.
+/// Valid values for it are:
+///
+/// - [`KT128`](crate::KT128)
+/// - [`KT256`](crate::KT256)
+///
+/// Any other value will fail to compile
+///
+/// # Examples
+///
+/// ```
+/// # use marsupial::{DualHasher, KT128};
+/// use sha3::{
+///     digest::{ExtendableOutput, XofReader},
+///     Shake128,
+/// };
+///
+/// let mut hasher = DualHasher::<KT128, _>::new(Shake128::default());
+/// hasher.update(b"foo").update(b"bar");
+/// let (k12, shake) = hasher.finalize();
+///
+/// let mut shake_output = [0; 32];
+/// shake.finalize_xof().read(&mut shake_output);
+/// # assert_eq!(k12, marsupial::hash::<KT128>(b"foobar"));
+/// ```
+pub struct DualHasher<N, D>(Hasher<N>, D);
+
+impl<N, D> DualHasher<N, D>
+where
+    N: SecurityLevel,
+    D: digest::Update,
+{
+    /// Construct a new [`DualHasher`] for the regular hash function, along
+    /// with the provided state of the other hash function
+    pub fn new(other: D) -> Self {
+        Self(Hasher::new(), other)
+    }
+
+    /// Add input bytes to both hash states. You can call this any number of
+    /// times, until the [`DualHasher`] is finalized
+    pub fn update(&mut self, input: impl AsRef<[u8]>) -> &mut Self {
+        let input = input.as_ref();
+        self.0.update(input);
+        self.1.update(input);
+        self
+    }
+
+    /// Finalize the KangarooTwelve hash state, consuming the [`DualHasher`],
+    /// and return the [`struct@Hash`](crate::Hash) of the input along with the
+    /// state of the other hash function, which is left for the caller to
+    /// finalize
+    #[must_use]
+    pub fn finalize(self) -> (N::Hash, D) {
+        (self.0.finalize(), self.1)
+    }
+
+    /// Split the [`DualHasher`] into its [`Hasher`] and the state of the
+    /// other hash function, without finalizing either
+    pub fn into_inner(self) -> (Hasher<N>, D) {
+        (self.0, self.1)
+    }
+}
+
+impl<N, D> Clone for DualHasher<N, D>
+where
+    N: SecurityLevel,
+    D: Clone,
+{
+    fn clone(&self) -> Self {
+        Self(self.0.clone(), self.1.clone())
+    }
+}
+
+impl<N, D> fmt::Debug for DualHasher<N, D>
+where
+    N: SecurityLevel,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DualHasher").finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,246 @@
+//! Error types returned by the fallible operations in this crate
+
+use core::{error, ffi::c_int, fmt};
+
+/// An error returned when converting a slice or vector of bytes into a
+/// [`struct@Hash`](crate::Hash) whose length doesn't match the input's
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TryFromSliceError {
+    expected: usize,
+    actual: usize,
+}
+
+impl TryFromSliceError {
+    #[inline]
+    pub(crate) fn new(expected: usize, actual: usize) -> Self {
+        Self { expected, actual }
+    }
+
+    /// The length of the [`struct@Hash`](crate::Hash) being converted into
+    /// (in bytes)
+    #[inline]
+    pub fn expected(&self) -> usize {
+        self.expected
+    }
+
+    /// The length of the input that was provided (in bytes)
+    #[inline]
+    pub fn actual(&self) -> usize {
+        self.actual
+    }
+}
+
+impl fmt::Display for TryFromSliceError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "expected {} bytes to convert into a hash, but got {}",
+            self.expected, self.actual
+        )
+    }
+}
+
+impl error::Error for TryFromSliceError {}
+
+/// An error returned when parsing a [`ContentId`](crate::cas::ContentId)
+/// from a string
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ContentIdParseError {
+    /// The string had the contained length (in bytes), rather than
+    /// [`BASE32_LEN`](crate::cas::BASE32_LEN)
+    InvalidLength(usize),
+
+    /// The string contained a character outside of the base32 alphabet
+    InvalidCharacter(char),
+
+    /// The padding bits at the end of the string weren't zero
+    NonCanonical,
+}
+
+impl fmt::Display for ContentIdParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::InvalidLength(length) => write!(
+                f,
+                "expected a content id of {} characters, but got {}",
+                crate::cas::BASE32_LEN,
+                length
+            ),
+            Self::InvalidCharacter(character) => {
+                write!(f, "invalid character {:?} in a content id", character)
+            }
+            Self::NonCanonical => write!(f, "the content id isn't canonically encoded"),
+        }
+    }
+}
+
+impl error::Error for ContentIdParseError {}
+
+/// An error returned when parsing a [`struct@Hash`](crate::Hash) from a
+/// string
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum HashParseError {
+    /// The string isn't valid in any of the accepted encodings
+    InvalidEncoding,
+
+    /// The string decoded to the wrong number of bytes
+    InvalidLength {
+        /// The length of the [`struct@Hash`](crate::Hash) being parsed (in
+        /// bytes)
+        expected: usize,
+
+        /// The length the string decoded to (in bytes)
+        actual: usize,
+    },
+}
+
+impl fmt::Display for HashParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::InvalidEncoding => write!(f, "the hash isn't validly encoded"),
+            Self::InvalidLength { expected, actual } => write!(
+                f,
+                "expected the hash to decode to {} bytes, but it decoded to {}",
+                expected, actual
+            ),
+        }
+    }
+}
+
+impl error::Error for HashParseError {}
+
+/// An error returned by [`Hasher::update_path`](crate::Hasher::update_path)
+/// when a path has no portable encoding
+#[cfg(feature = "std")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum PathEncodingError {
+    /// A component of the path wasn't valid UTF-8
+    NonUtf8,
+
+    /// The path had a root, a prefix (on Windows), or a `..` component
+    UnsupportedComponent,
+}
+
+#[cfg(feature = "std")]
+impl fmt::Display for PathEncodingError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::NonUtf8 => write!(f, "the path contains a component that isn't valid UTF-8"),
+            Self::UnsupportedComponent => {
+                write!(f, "the path isn't relative, or contains a `..` component")
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl error::Error for PathEncodingError {}
+
+/// An error returned by [`LimitedHasher::update`](crate::LimitedHasher::update)
+/// when the input would exceed the hasher's limit
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LimitExceededError {
+    limit: u64,
+}
+
+impl LimitExceededError {
+    #[inline]
+    pub(crate) fn new(limit: u64) -> Self {
+        Self { limit }
+    }
+
+    /// The maximum number of bytes the hasher accepts
+    #[inline]
+    pub fn limit(&self) -> u64 {
+        self.limit
+    }
+}
+
+impl fmt::Display for LimitExceededError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "the input exceeded the limit of {} bytes", self.limit)
+    }
+}
+
+impl error::Error for LimitExceededError {}
+
+/// An error returned by [`selftest`](crate::selftest) when one of its
+/// known-answer tests fails
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SelfTestError {
+    name: &'static str,
+}
+
+impl SelfTestError {
+    #[inline]
+    pub(crate) fn new(name: &'static str) -> Self {
+        Self { name }
+    }
+
+    /// A description of the known-answer test that failed, such as
+    /// `"KT128 of an empty input"`
+    #[inline]
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+}
+
+impl fmt::Display for SelfTestError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "the known-answer test for {} failed", self.name)
+    }
+}
+
+impl error::Error for SelfTestError {}
+
+/// An error returned when XKCP/K12 reports that an operation failed
+///
+/// The types in this crate keep their instances in the states XKCP/K12
+/// expects, so this isn't currently expected to occur, but XKCP/K12's
+/// functions do return status codes, which this surfaces
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct K12Error {
+    code: c_int,
+}
+
+impl K12Error {
+    #[inline]
+    pub(crate) fn new(code: c_int) -> Self {
+        Self { code }
+    }
+
+    /// The nonzero status code returned by XKCP/K12
+    #[inline]
+    pub fn code(&self) -> c_int {
+        self.code
+    }
+}
+
+impl fmt::Display for K12Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "XKCP/K12 returned the status code {}", self.code)
+    }
+}
+
+impl error::Error for K12Error {}
+
+/// An error returned by [`HashSinkOutput`](crate::HashSinkOutput) when its
+/// [`HashSink`](crate::HashSink) is dropped without being closed, so no
+/// [`struct@Hash`](crate::Hash) is ever delivered to it
+#[cfg(feature = "futures")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct SinkCanceledError;
+
+#[cfg(feature = "futures")]
+impl fmt::Display for SinkCanceledError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "the hash sink was dropped without being closed")
+    }
+}
+
+#[cfg(feature = "futures")]
+impl error::Error for SinkCanceledError {}
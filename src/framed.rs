@@ -0,0 +1,121 @@
+//! Unambiguous hashing of sequences of messages
+
+use crate::{Hasher, OutputReader, SecurityLevel};
+use core::fmt;
+
+/// An incremental hash state over a sequence of messages, rather than a
+/// single stream of bytes
+///
+/// Feeding several messages to a plain [`Hasher`] hashes their
+/// concatenation, so `["ab", "c"]` and `["a", "bc"]` produce the same
+/// [`struct@Hash`](crate::Hash). A [`FramedHasher`] avoids that by prefixing
+/// each message with its length
+///
+/// # Framing
+///
+/// Each message pushed is absorbed as its length (in bytes), encoded as a
+/// little-endian `u64`, followed by the message itself. No other bytes are
+/// added, so the digest of a sequence is the digest of its framed encoding
+/// under a plain [`Hasher`]. Note that this means a [`FramedHasher`] and a
+/// [`Hasher`] fed the same framed bytes produce the same output, which is also
+/// what [`Hasher::update_frame`] absorbs
+///
+/// The `N` parameter indicates the security strength level in number of bits.
+/// Valid values for it are:
+///
+/// - [`KT128`](crate::KT128)
+/// - [`KT256`](crate::KT256)
+///
+/// Any other value will fail to compile
+///
+/// # Examples
+///
+/// ```
+/// # use marsupial::{FramedHasher, KT128};
+/// let mut hasher = FramedHasher::<KT128>::new();
+/// hasher.push(b"ab");
+/// hasher.push(b"c");
+///
+/// let mut other = FramedHasher::<KT128>::new();
+/// other.push(b"a");
+/// other.push(b"bc");
+///
+/// assert_ne!(hasher.finalize(), other.finalize());
+/// ```
+pub struct FramedHasher<N>(Hasher<N>);
+
+impl<N> FramedHasher<N>
+where
+    N: SecurityLevel,
+{
+    /// Construct a new [`FramedHasher`] for the regular hash function
+    pub fn new() -> Self {
+        Self(Hasher::new())
+    }
+
+    /// Add a message to the sequence. You can call this any number of times,
+    /// until the [`FramedHasher`] is finalized
+    pub fn push(&mut self, message: &[u8]) {
+        self.0.update_frame(message);
+    }
+
+    /// Finalize the hash state, consuming the [`FramedHasher`], and return
+    /// the [`struct@Hash`](crate::Hash) of the sequence. This method is
+    /// equivalent to [`finalize_custom`](#method.finalize_custom) with an
+    /// empty customization string
+    #[must_use]
+    pub fn finalize(self) -> N::Hash {
+        self.0.finalize()
+    }
+
+    /// Finalize the hash state, consuming the [`FramedHasher`], and return
+    /// the [`struct@Hash`](crate::Hash) of the sequence
+    #[must_use]
+    pub fn finalize_custom(self, customization: &[u8]) -> N::Hash {
+        self.0.finalize_custom(customization)
+    }
+
+    /// Finalize the hash state, consuming the [`FramedHasher`] and returning
+    /// an [`OutputReader`], which can supply any number of output bytes.
+    /// This method is equivalent to
+    /// [`finalize_custom_xof`](#method.finalize_custom_xof) with an empty
+    /// customization string
+    #[must_use]
+    pub fn finalize_xof(self) -> OutputReader {
+        self.0.finalize_xof()
+    }
+
+    /// Finalize the hash state, consuming the [`FramedHasher`] and returning
+    /// an [`OutputReader`], which can supply any number of output bytes
+    #[must_use]
+    pub fn finalize_custom_xof(self, customization: &[u8]) -> OutputReader {
+        self.0.finalize_custom_xof(customization)
+    }
+}
+
+impl<N> Clone for FramedHasher<N>
+where
+    N: SecurityLevel,
+{
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<N> Default for FramedHasher<N>
+where
+    N: SecurityLevel,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<N> fmt::Debug for FramedHasher<N>
+where
+    N: SecurityLevel,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FramedHasher").finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,140 @@
+//! Access to the internal state of the XKCP/K12 instances backing this
+//! crate's types
+//!
+//! Everything in this module mirrors the layout of XKCP/K12's structures, and
+//! can change whenever the vendored sources are updated, without a major
+//! version bump. It's intended for white-box testing and comparing state
+//! against other implementations
+
+use crate::{Hasher, SecurityLevel};
+use core::{
+    ffi::{c_int, c_uint},
+    marker::PhantomData,
+};
+
+/// The state of a TurboSHAKE sponge, as stored by XKCP/K12
+pub use marsupial_sys::TurboSHAKE_Instance;
+
+/// The state of a [`Hasher`], with each of the fields of the underlying
+/// `KangarooTwelve_Instance` exposed by name
+#[derive(Clone, Copy, Debug)]
+pub struct HasherState {
+    /// The sponge the current leaf (or, before the first chunk has been
+    /// completed, the final node) is being absorbed into
+    pub queue_node: TurboSHAKE_Instance,
+
+    /// The sponge the final node is being absorbed into
+    pub final_node: TurboSHAKE_Instance,
+
+    /// The output length fixed at initialization, which is always 0 for
+    /// instances created by this crate
+    pub fixed_output_length: usize,
+
+    /// The number of chunks that have been completed so far
+    pub block_number: usize,
+
+    /// The number of bytes absorbed into the current chunk
+    pub queue_absorbed_len: c_uint,
+
+    /// The phase the instance is in: 1 while absorbing, 2 once final, and 3
+    /// while squeezing
+    pub phase: c_int,
+
+    /// The security strength level of the instance (in bits)
+    pub security_level: c_int,
+}
+
+impl HasherState {
+    /// Reconstruct a [`Hasher`] from a [`HasherState`]
+    ///
+    /// # Safety
+    ///
+    /// The state must be one XKCP/K12 could have produced, such as one taken
+    /// from a [`Hasher`] with the same security level. XKCP/K12 trusts the
+    /// indices it stores, so e.g. an out-of-range `byteIOIndex` leads to out
+    /// of bounds memory accesses
+    ///
+    /// # Panics
+    ///
+    /// Panics if [`security_level`](#structfield.security_level) doesn't match
+    /// `N`
+    pub unsafe fn into_hasher<N>(self) -> Hasher<N>
+    where
+        N: SecurityLevel,
+    {
+        assert_eq!(
+            self.security_level as usize,
+            N::BITS,
+            "the state has a different security level"
+        );
+        Hasher(
+            marsupial_sys::KangarooTwelve_Instance {
+                queueNode: self.queue_node,
+                finalNode: self.final_node,
+                fixedOutputLength: self.fixed_output_length,
+                blockNumber: self.block_number,
+                queueAbsorbedLen: self.queue_absorbed_len,
+                phase: self.phase,
+                securityLevel: self.security_level,
+            },
+            PhantomData,
+        )
+    }
+}
+
+impl<N> From<&Hasher<N>> for HasherState
+where
+    N: SecurityLevel,
+{
+    fn from(hasher: &Hasher<N>) -> Self {
+        Self {
+            queue_node: hasher.0.queueNode,
+            final_node: hasher.0.finalNode,
+            fixed_output_length: hasher.0.fixedOutputLength,
+            block_number: hasher.0.blockNumber,
+            queue_absorbed_len: hasher.0.queueAbsorbedLen,
+            phase: hasher.0.phase,
+            security_level: hasher.0.securityLevel,
+        }
+    }
+}
+
+/// Compares the underlying XKCP/K12 instances field by field, so two
+/// [`Hasher`]s are equal when they've absorbed the same input, regardless of
+/// how it was split into updates
+///
+/// This is meant for white-box tests
+impl<N> PartialEq for Hasher<N>
+where
+    N: SecurityLevel,
+{
+    fn eq(&self, other: &Self) -> bool {
+        fn sponge_eq(a: &TurboSHAKE_Instance, b: &TurboSHAKE_Instance) -> bool {
+            a.state == b.state
+                && a.byteIOIndex == b.byteIOIndex
+                && a.rate == b.rate
+                && a.squeezing == b.squeezing
+        }
+
+        let (a, b) = (&self.0, &other.0);
+        sponge_eq(&a.queueNode, &b.queueNode)
+            && sponge_eq(&a.finalNode, &b.finalNode)
+            && a.fixedOutputLength == b.fixedOutputLength
+            && a.blockNumber == b.blockNumber
+            && a.queueAbsorbedLen == b.queueAbsorbedLen
+            && a.phase == b.phase
+            && a.securityLevel == b.securityLevel
+    }
+}
+
+impl<N> Eq for Hasher<N> where N: SecurityLevel {}
+
+impl<N> From<Hasher<N>> for HasherState
+where
+    N: SecurityLevel,
+{
+    #[inline]
+    fn from(hasher: Hasher<N>) -> Self {
+        Self::from(&hasher)
+    }
+}
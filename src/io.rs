@@ -0,0 +1,142 @@
+//! Utilities for hashing data from [`std::io`] sources
+
+use crate::{Hasher, SecurityLevel, BUFFER_LEN};
+use std::{
+    fs::File,
+    io::{self, Read, Write},
+    path::Path,
+};
+
+/// Copy the entire contents of `reader` into `writer`, hashing the bytes as
+/// they pass through. On success, the total number of bytes copied is
+/// returned, along with the [`struct@Hash`](crate::Hash) of them
+///
+/// This is like [`std::io::copy`], except that the data is also fed to a
+/// [`Hasher`] in the same pass. Reads that fail with
+/// [`io::ErrorKind::Interrupted`] are retried, and any other error is
+/// returned immediately, in which case some data may already have been
+/// written
+///
+/// The `N` parameter indicates the security strength level in number of bits.
+/// Valid values for it are:
+///
+/// - [`KT128`](crate::KT128)
+/// - [`KT256`](crate::KT256)
+///
+/// Any other value will fail to compile
+///
+/// # Examples
+///
+/// ```
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// # use marsupial::KT128;
+/// let mut reader: &[u8] = b"foobarbaz";
+/// let mut writer = Vec::new();
+/// let (len, hash) = marsupial::copy_and_hash::<KT128, _, _>(&mut reader, &mut writer)?;
+/// assert_eq!(len, 9);
+/// assert_eq!(writer, b"foobarbaz");
+/// assert_eq!(hash, marsupial::hash::<KT128>(b"foobarbaz"));
+/// # Ok(())
+/// # }
+/// ```
+pub fn copy_and_hash<N, R, W>(reader: &mut R, writer: &mut W) -> io::Result<(u64, N::Hash)>
+where
+    N: SecurityLevel,
+    R: Read + ?Sized,
+    W: Write + ?Sized,
+{
+    let mut hasher = Hasher::<N>::new();
+    let total = update_and_copy(&mut hasher, reader, writer)?;
+    Ok((total, hasher.finalize()))
+}
+
+/// Hash the entire contents of `reader`, and compare the
+/// [`struct@Hash`](crate::Hash) of them against `expected` in constant time.
+/// If `expected` isn't the length of the canonical
+/// [`struct@Hash`](crate::Hash), this returns `Ok(false)`
+///
+/// Reads that fail with [`io::ErrorKind::Interrupted`] are retried, and any
+/// other error is returned immediately
+///
+/// The `N` parameter indicates the security strength level in number of bits.
+/// Valid values for it are:
+///
+/// - [`KT128`](crate::KT128)
+/// - [`KT256`](crate::KT256)
+///
+/// Any other value will fail to compile
+pub fn verify_reader<N, R>(mut reader: R, expected: &[u8]) -> io::Result<bool>
+where
+    N: SecurityLevel,
+    R: Read,
+{
+    let mut hasher = Hasher::<N>::new();
+    update_and_copy(&mut hasher, &mut reader, &mut io::sink())?;
+    Ok(hasher.finalize_verify(expected))
+}
+
+/// Hash the contents of the file at `path`, along with its length
+///
+/// The input to the hash function is the length of the file (in bytes),
+/// encoded as a little-endian `u64`, followed by the contents of the file.
+/// Because the length is committed to up front, a file's digest differs from
+/// that of any truncated or extended version of it, even if the contents are
+/// otherwise identical. Note that this means the result differs from hashing
+/// the contents alone
+///
+/// If the file's length changes while it's being read, an error of kind
+/// [`io::ErrorKind::InvalidData`] is returned
+///
+/// The `N` parameter indicates the security strength level in number of bits.
+/// Valid values for it are:
+///
+/// - [`KT128`](crate::KT128)
+/// - [`KT256`](crate::KT256)
+///
+/// Any other value will fail to compile
+pub fn hash_file_with_size<N, P>(path: P) -> io::Result<N::Hash>
+where
+    N: SecurityLevel,
+    P: AsRef<Path>,
+{
+    let mut file = File::open(path)?;
+    let len = file.metadata()?.len();
+
+    let mut hasher = Hasher::<N>::new();
+    hasher.update_u64_le(len);
+    if update_and_copy(&mut hasher, &mut file, &mut io::sink())? != len {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "the file's length changed while it was being hashed",
+        ));
+    }
+    Ok(hasher.finalize())
+}
+
+/// Feed the entire contents of `reader` to `hasher`, copying them into
+/// `writer` along the way, and return the number of bytes read
+fn update_and_copy<N, R, W>(
+    hasher: &mut Hasher<N>,
+    reader: &mut R,
+    writer: &mut W,
+) -> io::Result<u64>
+where
+    N: SecurityLevel,
+    R: Read + ?Sized,
+    W: Write + ?Sized,
+{
+    let mut buffer = [0; BUFFER_LEN];
+    let mut total = 0;
+    loop {
+        let len = match reader.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(len) => len,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+        hasher.update(&buffer[..len]);
+        writer.write_all(&buffer[..len])?;
+        total += len as u64;
+    }
+    Ok(total)
+}
@@ -27,22 +27,154 @@
 //! output_reader.squeeze(&mut output);
 //! assert_eq!(&output[..32], hash1.as_bytes());
 //!
-//! // emit the hash as hexadecimal (does not work for now)
-//! //println!("{}", hash1.to_hex());
+//! // emit the hash as hexadecimal
+//! # #[cfg(feature = "alloc")]
+//! println!("{}", hash1.to_hex());
 //! # Ok(())
 //! # }
 //! ```
+//!
+//! # `no_std` support
+//!
+//! Everything that doesn't require an allocator or the standard library is
+//! available with `default-features = false`, in which case this crate is
+//! `#![no_std]`. The `alloc` feature brings back the functionality that
+//! allocates, and the `std` feature the [`std::io`] integrations
+
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+#[cfg(feature = "alloc")]
+use alloc::{boxed::Box, string::String, vec, vec::Vec};
+use core::{fmt, marker::PhantomData, mem::MaybeUninit, ptr};
 
-use std::{fmt, marker::PhantomData, mem::MaybeUninit};
+mod builder;
+pub mod cas;
+#[cfg(feature = "digest")]
+mod dual;
+mod error;
+mod framed;
+#[cfg(feature = "std")]
+mod io;
+mod limited;
+mod self_test;
+#[cfg(feature = "futures")]
+mod sink;
+mod std_hash;
+mod structured;
+
+#[cfg(feature = "unstable-internals")]
+pub mod internals;
+
+#[cfg(feature = "proptest")]
+pub mod strategies;
 
 #[cfg(test)]
 mod test;
 
+pub use builder::{ConfiguredHasher, HasherBuilder};
+#[cfg(feature = "digest")]
+pub use dual::DualHasher;
+#[cfg(feature = "std")]
+pub use error::PathEncodingError;
+#[cfg(feature = "futures")]
+pub use error::SinkCanceledError;
+pub use error::{
+    ContentIdParseError, HashParseError, K12Error, LimitExceededError, SelfTestError,
+    TryFromSliceError,
+};
+pub use framed::FramedHasher;
+#[cfg(feature = "std")]
+pub use io::{copy_and_hash, hash_file_with_size, verify_reader};
+pub use limited::LimitedHasher;
+pub use self_test::selftest;
+#[cfg(feature = "futures")]
+pub use sink::{HashSink, HashSinkOutput};
+pub use std_hash::{K12BuildHasher, K12StdHasher};
+pub use structured::StructHasher;
+
+/// The name of the XKCP/K12 implementation this crate was built with, such as
+/// `"Optimized64"` or `"Plain64"`. It is selected at build time based on the
+/// target
+///
+/// Note that the `Optimized64` implementations additionally choose between
+/// SSSE3, AVX2, and AVX512 code at runtime, depending on the CPU, which isn't
+/// reflected here
+pub const fn active_implementation() -> &'static str {
+    marsupial_sys::IMPLEMENTATION
+}
+
+/// The size of the chunks KangarooTwelve splits its input into (in bytes)
+///
+/// Inputs longer than this are hashed as a two-level tree: the first chunk
+/// goes directly into the final node, each subsequent chunk is hashed into a
+/// leaf whose chaining value is appended to the final node, and the final
+/// node is then hashed to produce the output. This is what lets the SIMD
+/// implementations hash several chunks in parallel
+///
+/// XKCP/K12 doesn't expose the leaf and final node computations separately,
+/// so neither does this crate. Building other tree structures on top of the
+/// same permutation isn't possible through it
+pub const CHUNK_SIZE: usize = 8192;
+
+/// The size of the buffer used when staging data read from an
+/// [`io::Read`](std::io::Read) for a [`Hasher`]. XKCP/K12 only hashes chunks
+/// in parallel when they're passed to it in the same call, so this spans
+/// eight of them, which is as many as its widest (AVX512) permutation
+/// processes at once
+#[cfg(feature = "std")]
+const BUFFER_LEN: usize = 8 * CHUNK_SIZE;
+
+/// The size of the scratch buffers [`OutputReader`] helpers squeeze output
+/// they don't return into. These live on the stack, so reusing them across
+/// calls wouldn't save any allocations
+const SCRATCH_LEN: usize = 4 * 1024;
+
+/// The revision of XKCP/K12 this crate was built against, for auditing
+/// purposes. This is the commit hash of the vendored sources, `"system"` when
+/// the `system-libk12` feature is enabled, or `"unknown"` if the commit
+/// couldn't be determined at build time
+pub const XKCP_VERSION: &str = marsupial_sys::XKCP_VERSION;
+
+/// The C compiler flags the vendored XKCP/K12 sources were built with,
+/// separated by spaces, for verifying reproducible builds. This is empty when
+/// the `system-libk12` feature is enabled
+///
+/// With the `reproducible` feature enabled, the optimization level is pinned
+/// to `-O2` rather than following the cargo profile, and flags from the
+/// `CFLAGS` family of environment variables are rejected, so this only
+/// depends on the target and the compiler
+pub const C_FLAGS: &str = marsupial_sys::CFLAGS;
+
 /// An internal trait used to prevent foreign implementations of the
 /// [`SecurityLevel`] trait
 trait Sealed {}
 
 /// A trait representing a valid [`Hasher`] security level
+///
+/// This trait is sealed, so [`KT128`] and [`KT256`] are the only types
+/// implementing it. Using anything else as a security level fails to
+/// compile:
+///
+/// ```compile_fail,E0599
+/// # use marsupial::Hasher;
+/// let hasher = Hasher::<u8>::new();
+/// ```
+///
+/// Including types defined outside of this crate:
+///
+/// ```compile_fail,E0277
+/// # use marsupial::{Hash, SecurityLevel};
+/// struct KT512;
+///
+/// impl SecurityLevel for KT512 {
+///     const BITS: usize = 512;
+///     const HASH_ARRAY_LENGTH: usize = 128;
+///     type Hash = Hash<128>;
+/// }
+/// ```
 #[allow(private_bounds)]
 pub trait SecurityLevel: Sealed {
     /// The security strength level, represented in terms of bits
@@ -54,7 +186,43 @@ pub trait SecurityLevel: Sealed {
 
     /// The canonical [`struct@Hash`] length associated with this
     /// [`SecurityLevel`]
+    #[cfg(feature = "alloc")]
     type Hash: Default + fmt::Debug + Eq + PartialEq + Into<Vec<u8>> + HashContainer;
+
+    /// The canonical [`struct@Hash`] length associated with this
+    /// [`SecurityLevel`]
+    #[cfg(not(feature = "alloc"))]
+    type Hash: Default + fmt::Debug + Eq + PartialEq + HashContainer;
+
+    /// The canonical [`struct@Hash`] length associated with this
+    /// [`SecurityLevel`], as a [`typenum`](digest::typenum) number
+    #[cfg(feature = "digest")]
+    type OutputSize: digest::generic_array::ArrayLength<u8>;
+
+    /// Construct a zeroed [`struct@Hash`] of the canonical length, for
+    /// generic code to fill
+    ///
+    /// An array of [`HASH_ARRAY_LENGTH`](Self::HASH_ARRAY_LENGTH) bytes can't
+    /// be named in code generic over the [`SecurityLevel`] on stable Rust, so
+    /// this is the way to get an output buffer of the right size. It can be
+    /// filled with [`Hasher::finalize_into_hash`]:
+    ///
+    /// ```
+    /// # use marsupial::{Hasher, SecurityLevel, KT128};
+    /// fn digest_of<N: SecurityLevel>(input: &[u8]) -> N::Hash {
+    ///     let mut out = N::new_output_buffer();
+    ///     let mut hasher = Hasher::<N>::new();
+    ///     hasher.update(input);
+    ///     hasher.finalize_into_hash(&mut out);
+    ///     out
+    /// }
+    ///
+    /// assert_eq!(digest_of::<KT128>(b"foo"), marsupial::hash::<KT128>(b"foo"));
+    /// ```
+    #[must_use]
+    fn new_output_buffer() -> Self::Hash {
+        Self::Hash::default()
+    }
 }
 
 /// The security strength level associated with the KT128 extendable output
@@ -67,8 +235,19 @@ impl SecurityLevel for KT128 {
     const BITS: usize = 128;
     const HASH_ARRAY_LENGTH: usize = 32;
     type Hash = Hash<32>;
+    #[cfg(feature = "digest")]
+    type OutputSize = digest::typenum::U32;
 }
 
+//NOTE: `Hasher::finalize_custom` squeezes `Hash::LEN` bytes into the hash, so
+//      a mismatch between it and `HASH_ARRAY_LENGTH`, or an empty hash, must
+//      not compile
+const _: () = assert!(<KT128 as SecurityLevel>::HASH_ARRAY_LENGTH != 0);
+const _: () = assert!(
+    <KT128 as SecurityLevel>::HASH_ARRAY_LENGTH
+        == <<KT128 as SecurityLevel>::Hash as HashContainer>::LEN
+);
+
 /// The security strength level associated with the KT256 extendable output
 /// function
 pub struct KT256;
@@ -79,8 +258,30 @@ impl SecurityLevel for KT256 {
     const BITS: usize = 256;
     const HASH_ARRAY_LENGTH: usize = 64;
     type Hash = Hash<64>;
+    #[cfg(feature = "digest")]
+    type OutputSize = digest::typenum::U64;
 }
 
+const _: () = assert!(<KT256 as SecurityLevel>::HASH_ARRAY_LENGTH != 0);
+const _: () = assert!(
+    <KT256 as SecurityLevel>::HASH_ARRAY_LENGTH
+        == <<KT256 as SecurityLevel>::Hash as HashContainer>::LEN
+);
+
+//NOTE: these fail to compile if a change to the xkcp instance (e.g. a raw
+//      pointer being added to it) makes any of the public types lose their
+//      auto traits
+const _: fn() = || {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<Hasher<KT128>>();
+    assert_send_sync::<Hasher<KT256>>();
+    assert_send_sync::<FramedHasher<KT128>>();
+    assert_send_sync::<FramedHasher<KT256>>();
+    assert_send_sync::<OutputReader>();
+    assert_send_sync::<Hash<32>>();
+    assert_send_sync::<Hash<64>>();
+};
+
 /// An internal trait used to allow the [`struct@Hash`] type to be polymorphic
 /// over the number of bytes it contains while still working as a return
 /// type from [`Hasher`] methods
@@ -88,8 +289,11 @@ trait HashContainer {
     /// A raw pointer to the memory region containing the hash
     fn ptr(&mut self) -> *mut u8;
 
+    /// The memory region containing the hash
+    fn as_slice(&self) -> &[u8];
+
     /// The length of the memory region containing the hash (in bytes)
-    fn len() -> usize;
+    const LEN: usize;
 }
 
 /// Hash a slice of bytes all at once. For multiple writes, the optional
@@ -104,6 +308,7 @@ trait HashContainer {
 /// Any other value will fail to compile
 ///
 /// [`Hasher`]: struct.Hasher.html
+#[must_use]
 pub fn hash<N>(input: &[u8]) -> N::Hash
 where
     N: SecurityLevel,
@@ -113,6 +318,288 @@ where
     hasher.finalize()
 }
 
+/// Hash a slice of bytes all at once, and return the [`struct@Hash`] as a
+/// lowercase hexadecimal string. This is equivalent to calling
+/// [`Hash::to_hex`] on the output of [`hash`]
+///
+/// The `N` parameter indicates the security strength level in number of bits.
+/// Valid values for it are:
+///
+/// - [`KT128`]
+/// - [`KT256`]
+///
+/// Any other value will fail to compile
+#[cfg(feature = "alloc")]
+#[must_use]
+pub fn hash_hex<N>(input: &[u8]) -> String
+where
+    N: SecurityLevel,
+{
+    encode_hex(hash::<N>(input).as_slice(), LOWER_HEX_DIGITS, None)
+}
+
+const LOWER_HEX_DIGITS: &[u8; 16] = b"0123456789abcdef";
+
+#[cfg(feature = "alloc")]
+const UPPER_HEX_DIGITS: &[u8; 16] = b"0123456789ABCDEF";
+
+/// Encode a slice of bytes as a hexadecimal string using the provided digits,
+/// optionally inserting a separator between every group of the given number
+/// of nibbles
+#[cfg(feature = "alloc")]
+fn encode_hex(bytes: &[u8], digits: &[u8; 16], separator: Option<(char, usize)>) -> String {
+    let mut hex = String::with_capacity(bytes.len() * 2);
+    let nibbles = bytes.iter().flat_map(|byte| [byte >> 4, byte & 0xf]);
+    for (i, nibble) in nibbles.enumerate() {
+        if let Some((separator, group)) = separator {
+            if i != 0 && i % group == 0 {
+                hex.push(separator);
+            }
+        }
+        hex.push(digits[nibble as usize] as char);
+    }
+    hex
+}
+
+/// Hash the in-memory representation of a value all at once
+///
+/// Note that the bytes hashed are exactly those of `value` as laid out in
+/// memory, so the resulting [`struct@Hash`] depends on the target's
+/// endianness, as well as on the type's layout. It is only portable across
+/// architectures if `T` is `#[repr(C)]` (or otherwise has a defined layout)
+/// and all of its integer fields are stored in a fixed byte order, such as
+/// with [`zerocopy::byteorder`]. [`zerocopy::AsBytes`] already guarantees
+/// that `T` has no padding
+///
+/// The `N` parameter indicates the security strength level in number of bits.
+/// Valid values for it are:
+///
+/// - [`KT128`]
+/// - [`KT256`]
+///
+/// Any other value will fail to compile
+#[cfg(feature = "zerocopy")]
+#[must_use]
+pub fn hash_typed<N, T>(value: &T) -> N::Hash
+where
+    N: SecurityLevel,
+    T: zerocopy::AsBytes + ?Sized,
+{
+    hash::<N>(value.as_bytes())
+}
+
+/// Hash the bytes produced by an iterator all at once, without collecting
+/// them first. The bytes are staged in a fixed-size buffer on the stack,
+/// which is passed to [`Hasher::update`] whenever it fills up
+///
+/// The `N` parameter indicates the security strength level in number of bits.
+/// Valid values for it are:
+///
+/// - [`KT128`]
+/// - [`KT256`]
+///
+/// Any other value will fail to compile
+#[must_use]
+pub fn hash_iter<N, I>(iter: I) -> N::Hash
+where
+    N: SecurityLevel,
+    I: IntoIterator<Item = u8>,
+{
+    let mut hasher = Hasher::<N>::new();
+    //NOTE: this is kept to a single chunk, as a buffer spanning several of
+    //      them would be too large to put on the stack of a `no_std` target
+    let mut buffer = [0; CHUNK_SIZE];
+    let mut len = 0;
+    for byte in iter {
+        buffer[len] = byte;
+        len += 1;
+        if len == CHUNK_SIZE {
+            hasher.update(&buffer[..]);
+            len = 0;
+        }
+    }
+    hasher.update(&buffer[..len]);
+    hasher.finalize()
+}
+
+/// Hash a slice of bytes all at once, returning an output of each of the
+/// requested lengths (in bytes), in the same order
+///
+/// Extended output has the prefix property: the first `M` bytes of any
+/// output longer than `M` bytes are the output of length `M`. So, the output
+/// is only squeezed once, to the longest length requested, and every result
+/// is a prefix of it
+///
+/// The `N` parameter indicates the security strength level in number of bits.
+/// Valid values for it are:
+///
+/// - [`KT128`]
+/// - [`KT256`]
+///
+/// Any other value will fail to compile
+#[cfg(feature = "alloc")]
+#[must_use]
+pub fn hash_multi_len<N>(input: &[u8], lens: &[usize]) -> Vec<Vec<u8>>
+where
+    N: SecurityLevel,
+{
+    let mut hasher = Hasher::<N>::new();
+    hasher.update(input);
+    let mut output = vec![0; lens.iter().copied().max().unwrap_or(0)];
+    hasher.finalize_xof().squeeze(&mut output);
+    lens.iter().map(|len| output[..*len].to_vec()).collect()
+}
+
+/// Produce a permutation of `0..n` determined by `seed`, using the extended
+/// output of its hash as the source of randomness for a Fisher–Yates shuffle
+///
+/// This is meant for reproducibility, such as deterministic sampling, where
+/// everyone with the seed must arrive at the same permutation. The result is
+/// only as unpredictable as the seed, and no claims are made about it beyond
+/// those made about the extendable output function itself
+///
+/// # Algorithm
+///
+/// For `i` from `n - 1` down to `1`, the index to swap with `i` is drawn
+/// uniformly from `0..=i`, by reading little-endian `u64`s from the output
+/// until one falls below the largest multiple of `i + 1` that fits (to avoid
+/// modulo bias), and reducing it modulo `i + 1`. This is part of the stable
+/// output of the function, so it won't change between versions
+///
+/// The `N` parameter indicates the security strength level in number of bits.
+/// Valid values for it are:
+///
+/// - [`KT128`]
+/// - [`KT256`]
+///
+/// Any other value will fail to compile
+///
+/// # Examples
+///
+/// ```
+/// # use marsupial::KT128;
+/// let permutation = marsupial::shuffle_indices::<KT128>(b"seed", 5);
+/// assert_eq!(permutation, marsupial::shuffle_indices::<KT128>(b"seed", 5));
+///
+/// let mut sorted = permutation.clone();
+/// sorted.sort();
+/// assert_eq!(sorted, [0, 1, 2, 3, 4]);
+/// ```
+#[cfg(feature = "alloc")]
+#[must_use]
+pub fn shuffle_indices<N>(seed: &[u8], n: usize) -> Vec<usize>
+where
+    N: SecurityLevel,
+{
+    let mut hasher = Hasher::<N>::new();
+    hasher.update(seed);
+    let mut output_reader = hasher.finalize_xof();
+
+    let mut indices: Vec<usize> = (0..n).collect();
+    for i in (1..n).rev() {
+        let bound = i as u64 + 1;
+
+        //NOTE: 2^64 mod bound values at the top of the range would make the
+        //      lower remainders more likely, so they're drawn again
+        let rejected = (u64::MAX % bound + 1) % bound;
+        let j = loop {
+            let mut bytes = [0; 8];
+            output_reader.squeeze(&mut bytes);
+            let value = u64::from_le_bytes(bytes);
+            if value <= u64::MAX - rejected {
+                break value % bound;
+            }
+        };
+        indices.swap(i, j as usize);
+    }
+    indices
+}
+
+/// Hash a slice of bytes all at once, returning the [`struct@Hash`] as a
+/// [`GenericArray`](digest::generic_array::GenericArray), for use with
+/// [`generic-array`](https://crates.io/crates/generic-array)-based APIs
+///
+/// The `N` parameter indicates the security strength level in number of bits.
+/// Valid values for it are:
+///
+/// - [`KT128`]
+/// - [`KT256`]
+///
+/// Any other value will fail to compile
+#[cfg(feature = "digest")]
+#[must_use]
+pub fn hash_generic_array<N>(input: &[u8]) -> digest::generic_array::GenericArray<u8, N::OutputSize>
+where
+    N: SecurityLevel,
+{
+    digest::generic_array::GenericArray::clone_from_slice(hash::<N>(input).as_slice())
+}
+
+/// Hash the concatenation of several slices of bytes all at once. This is
+/// equivalent to calling [`hash`] on the slices joined together, but doesn't
+/// allocate a buffer to join them in
+///
+/// The `N` parameter indicates the security strength level in number of bits.
+/// Valid values for it are:
+///
+/// - [`KT128`]
+/// - [`KT256`]
+///
+/// Any other value will fail to compile
+#[must_use]
+pub fn hash_slices<N>(slices: &[&[u8]]) -> N::Hash
+where
+    N: SecurityLevel,
+{
+    let mut hasher = Hasher::<N>::new();
+    for slice in slices {
+        hasher.update(slice);
+    }
+    hasher.finalize()
+}
+
+/// Hash `prefix` followed by `body` all at once. This is equivalent to
+/// calling [`hash`] on `prefix` and `body` joined together, but doesn't
+/// allocate a buffer to join them in
+///
+/// KangarooTwelve only absorbs its input front to back, so a header that's
+/// only known after reading the body (e.g. its length or a checksum) still
+/// has to be hashed before it. This makes that ordering explicit
+///
+/// The `N` parameter indicates the security strength level in number of bits.
+/// Valid values for it are:
+///
+/// - [`KT128`]
+/// - [`KT256`]
+///
+/// Any other value will fail to compile
+#[must_use]
+pub fn hash_with_prefix<N>(prefix: &[u8], body: &[u8]) -> N::Hash
+where
+    N: SecurityLevel,
+{
+    hash_slices::<N>(&[prefix, body])
+}
+
+/// Hash `body` followed by `suffix` all at once. This is equivalent to
+/// calling [`hash`] on `body` and `suffix` joined together, but doesn't
+/// allocate a buffer to join them in
+///
+/// The `N` parameter indicates the security strength level in number of bits.
+/// Valid values for it are:
+///
+/// - [`KT128`]
+/// - [`KT256`]
+///
+/// Any other value will fail to compile
+#[must_use]
+pub fn hash_with_suffix<N>(body: &[u8], suffix: &[u8]) -> N::Hash
+where
+    N: SecurityLevel,
+{
+    hash_slices::<N>(&[body, suffix])
+}
+
 /// An incremental hash state that can accept any number of writes
 ///
 /// The `N` parameter indicates the security strength level in number of bits.
@@ -123,6 +610,12 @@ where
 ///
 /// Any other value will fail to compile
 ///
+/// # Thread safety
+///
+/// [`Hasher`] is both [`Send`] and [`Sync`]. It owns its entire state, which
+/// contains no pointers, so it can be moved to another thread freely, and
+/// every method that modifies it takes `&mut self`
+///
 /// # Examples
 ///
 /// ```
@@ -145,19 +638,61 @@ where
 /// # Ok(())
 /// # }
 /// ```
-#[derive(Clone)]
 pub struct Hasher<N>(marsupial_sys::KangarooTwelve_Instance, PhantomData<N>);
 
 impl<N> Hasher<N>
 where
     N: SecurityLevel,
 {
+    /// The capacity of the underlying sponge (in bits), which is twice the
+    /// security strength level
+    pub const CAPACITY_BITS: usize = 2 * N::BITS;
+
+    /// The rate of the underlying sponge (in bits). This is the width of the
+    /// Keccak-p\[1600\] permutation minus its capacity
+    pub const RATE_BITS: usize = 1600 - Self::CAPACITY_BITS;
+
     /// The number of bytes hashed or output per block
-    pub const RATE: usize = (1600 - (2 * N::BITS)) / 8;
+    pub const RATE: usize = Self::RATE_BITS / 8;
+
+    /// The number of rounds of the Keccak-p\[1600\] permutation applied per
+    /// block, which is the "twelve" in KangarooTwelve
+    pub const ROUNDS: usize = 12;
+
+    /// The length of the canonical [`struct@Hash`] produced by this
+    /// [`Hasher`] (in bytes). This is equivalent to
+    /// [`SecurityLevel::HASH_ARRAY_LENGTH`]
+    pub const fn output_len() -> usize {
+        N::HASH_ARRAY_LENGTH
+    }
+
+    /// The security strength level of this [`Hasher`] (in bits). This is
+    /// equivalent to [`SecurityLevel::BITS`]
+    pub const fn security_bits() -> usize {
+        N::BITS
+    }
 
     /// Construct a new [`Hasher`] for the regular hash function
+    ///
+    /// A [`Hasher`] never allocates, as its whole state is stored inline, so
+    /// constructing one only amounts to initializing that state. There's
+    /// nothing for pooling or reusing [`Hasher`]s to save, beyond that
+    ///
+    /// With the `self-test` feature enabled, the first call to this in a
+    /// process runs [`selftest`], and panics if it fails
     pub fn new() -> Self {
-        let mut inner = MaybeUninit::uninit();
+        #[cfg(feature = "self-test")]
+        self_test::check_dispatch_once();
+
+        Self::new_unchecked()
+    }
+
+    /// Construct a new [`Hasher`] for the regular hash function, without
+    /// running [`selftest`] first. This is what [`selftest`] itself uses
+    pub(crate) fn new_unchecked() -> Self {
+        //NOTE: the instance is zeroed before XKCP/K12 initializes it, so that
+        //      no field is left uninitialized even if it doesn't set every one
+        let mut inner = MaybeUninit::zeroed();
         let inner = unsafe {
             let ret =
                 marsupial_sys::KangarooTwelve_Initialize(inner.as_mut_ptr(), N::BITS as i32, 0);
@@ -174,41 +709,412 @@ where
         Self(inner, PhantomData)
     }
 
+    /// Construct a new [`Hasher`] for the regular hash function, given a hint
+    /// of how many bytes of input it will be fed
+    ///
+    /// The hint is advisory, and is currently ignored, making this equivalent
+    /// to [`new`](#method.new). It exists so that internal buffering can make
+    /// use of it in the future without an API change
+    pub fn with_capacity_hint(hint: usize) -> Self {
+        let _ = hint;
+        Self::new()
+    }
+
+    /// Construct a new [`Hasher`] for the regular hash function, with
+    /// `prefix` already absorbed as the start of the input. This is
+    /// equivalent to calling [`update`](#method.update) with `prefix` on a
+    /// new [`Hasher`]
+    ///
+    /// This is not a customization string, which KangarooTwelve places after
+    /// the input (see [`finalize_custom`](#method.finalize_custom)). A prefix
+    /// is part of the input itself, so nothing separates it from the rest of
+    /// the input: the prefix `b"ab"` followed by `b"c"` hashes the same as
+    /// the prefix `b"a"` followed by `b"bc"`. If that matters, use a prefix
+    /// of fixed length, or one framed by [`update_frame`](#method.update_frame)
+    ///
+    /// ```
+    /// # use marsupial::{KT128, Hasher};
+    /// let mut hasher = Hasher::<KT128>::new_with_prefix(b"foo");
+    /// hasher.update(b"bar");
+    /// assert_eq!(hasher.finalize(), marsupial::hash::<KT128>(b"foobar"));
+    /// ```
+    pub fn new_with_prefix(prefix: &[u8]) -> Self {
+        let mut hasher = Self::new();
+        hasher.update(prefix);
+        hasher
+    }
+
     /// Add input bytes to the hash state. You can call this any number of
     /// times, until the [`Hasher`] is finalized
-    pub fn update(&mut self, input: &[u8]) {
+    ///
+    /// Anything that can be viewed as a slice of bytes is accepted, so
+    /// `&[u8]`, `&[u8; N]`, `Vec<u8>`, `&str`, and `String` can all be passed
+    /// directly
+    ///
+    /// The input is passed to XKCP/K12 in a single call, however large it
+    /// is. XKCP/K12 can only hash chunks in parallel when they're passed to it
+    /// together, so a few large calls are faster than many small ones
+    ///
+    /// The [`Hasher`] is returned, so calls can be chained:
+    ///
+    /// ```
+    /// # use marsupial::{KT128, Hasher};
+    /// let mut hasher = Hasher::<KT128>::new();
+    /// hasher.update(b"foo").update(b"bar").update(b"baz");
+    /// assert_eq!(hasher.finalize(), marsupial::hash::<KT128>(b"foobarbaz"));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the total input would exceed `usize::MAX` chunks of
+    /// [`CHUNK_SIZE`] bytes, as XKCP/K12 counts them in a `size_t`. This is
+    /// only reachable on 32-bit targets, after 32 TiB of input
+    pub fn update(&mut self, input: impl AsRef<[u8]>) -> &mut Self {
+        let input = input.as_ref();
+
+        //NOTE: this overestimates the number of chunks completed by this
+        //      update by at most one, which is also what leaves room for the
+        //      final chunk to be counted in `KangarooTwelve_Final`
+        assert!(
+            self.0
+                .blockNumber
+                .checked_add(input.len() / CHUNK_SIZE + 1)
+                .is_some(),
+            "the input is too long for the chunk counter"
+        );
         unsafe {
             let ret =
                 marsupial_sys::KangarooTwelve_Update(&mut self.0, input.as_ptr(), input.len());
-            debug_assert_eq!(0, ret);
+
+            //NOTE: this is the only way for `KangarooTwelve_Update` to fail.
+            //      finalizing consumes the `Hasher`, so this shouldn't be
+            //      reachable, but it's checked in release builds as well since
+            //      absorbing into a squeezing instance would silently produce
+            //      wrong output
+            assert_eq!(0, ret, "this instance has already been finalized");
         }
+        self
+    }
+
+    /// Add a frame containing `data` to the hash state. The frame consists of
+    /// the length of `data` (in bytes), encoded as a little-endian `u64`,
+    /// followed by `data` itself
+    ///
+    /// As each frame is prefixed with its length, a sequence of frames can't
+    /// be confused with a different sequence, so e.g. framing `b"ab"` and
+    /// then `b"c"` produces a different hash than framing `b"a"` and then
+    /// `b"bc"`. This is the same framing [`FramedHasher`] uses
+    pub fn update_frame(&mut self, data: &[u8]) -> &mut Self {
+        self.update_u64_le(data.len() as u64).update(data)
+    }
+
+    /// Add a portable encoding of a relative path to the hash state, so that
+    /// the same path produces the same hash on every platform
+    ///
+    /// The encoding consists of the number of components in the path, as a
+    /// little-endian `u64`, followed by a [frame](#method.update_frame)
+    /// containing the UTF-8 bytes of each component. `.` components are
+    /// skipped, and separators aren't encoded, so `foo/bar`, `./foo//bar/`
+    /// and (on Windows) `foo\bar` are all encoded the same way
+    ///
+    /// Paths are compared byte for byte, without any normalization, so paths
+    /// that a case-insensitive filesystem (or one normalizing Unicode, such
+    /// as on macOS) considers the same can still produce different hashes.
+    /// On Windows, `\` is a separator, while elsewhere it is part of a
+    /// component
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`PathEncodingError`] if a component isn't valid UTF-8, or
+    /// if the path isn't relative or contains a `..` component, in which
+    /// case nothing is added to the hash state
+    #[cfg(feature = "std")]
+    pub fn update_path(&mut self, path: &std::path::Path) -> Result<&mut Self, PathEncodingError> {
+        use std::path::Component;
+
+        let mut components = 0u64;
+        for component in path.components() {
+            match component {
+                Component::Normal(name) if name.to_str().is_some() => components += 1,
+                Component::Normal(_) => return Err(PathEncodingError::NonUtf8),
+                Component::CurDir => (),
+                _ => return Err(PathEncodingError::UnsupportedComponent),
+            }
+        }
+
+        self.update_u64_le(components);
+        for component in path.components() {
+            //NOTE: every component was checked to be UTF-8 above, and UTF-8
+            //      is encoded as itself in an `OsStr` on every platform
+            if let Component::Normal(name) = component {
+                self.update_frame(name.as_encoded_bytes());
+            }
+        }
+        Ok(self)
+    }
+
+    /// Add the first `n` bytes of `buf` to the hash state, and return the
+    /// rest of it, for parsers that hash their input as they consume it
+    ///
+    /// ```
+    /// # use marsupial::{KT128, Hasher};
+    /// let mut hasher = Hasher::<KT128>::new();
+    /// let rest = hasher.update_prefix(b"foobarbaz", 3);
+    /// assert_eq!(rest, b"barbaz");
+    /// assert_eq!(hasher.finalize(), marsupial::hash::<KT128>(b"foo"));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is greater than the length of `buf`
+    #[inline]
+    pub fn update_prefix<'a>(&mut self, buf: &'a [u8], n: usize) -> &'a [u8] {
+        let (prefix, rest) = buf.split_at(n);
+        self.update(prefix);
+        rest
+    }
+
+    /// Add the UTF-8 bytes of a string to the hash state. This is equivalent
+    /// to calling [`update`](#method.update) with `s.as_bytes()`
+    #[inline]
+    pub fn update_str(&mut self, s: &str) -> &mut Self {
+        self.update(s.as_bytes())
+    }
+
+    /// Add a `u16` to the hash state, encoded in little-endian byte order
+    #[inline]
+    pub fn update_u16_le(&mut self, value: u16) -> &mut Self {
+        self.update(value.to_le_bytes())
+    }
+
+    /// Add a `u16` to the hash state, encoded in big-endian byte order
+    #[inline]
+    pub fn update_u16_be(&mut self, value: u16) -> &mut Self {
+        self.update(value.to_be_bytes())
+    }
+
+    /// Add a `u32` to the hash state, encoded in little-endian byte order
+    #[inline]
+    pub fn update_u32_le(&mut self, value: u32) -> &mut Self {
+        self.update(value.to_le_bytes())
+    }
+
+    /// Add a `u32` to the hash state, encoded in big-endian byte order
+    #[inline]
+    pub fn update_u32_be(&mut self, value: u32) -> &mut Self {
+        self.update(value.to_be_bytes())
+    }
+
+    /// Add a `u64` to the hash state, encoded in little-endian byte order
+    #[inline]
+    pub fn update_u64_le(&mut self, value: u64) -> &mut Self {
+        self.update(value.to_le_bytes())
+    }
+
+    /// Add a `u64` to the hash state, encoded in big-endian byte order
+    #[inline]
+    pub fn update_u64_be(&mut self, value: u64) -> &mut Self {
+        self.update(value.to_be_bytes())
+    }
+
+    /// Add all of the remaining bytes in a [`bytes::Buf`] to the hash state,
+    /// advancing it to the end. Non-contiguous buffers are hashed chunk by
+    /// chunk, without being copied into a contiguous one first
+    #[cfg(feature = "bytes")]
+    pub fn update_buf<B>(&mut self, buf: &mut B) -> &mut Self
+    where
+        B: bytes::Buf,
+    {
+        while buf.has_remaining() {
+            let chunk = buf.chunk();
+            let len = chunk.len();
+            self.update(chunk);
+            buf.advance(len);
+        }
+        self
     }
 
     /// Finalize the hash state, consuming the [`Hasher`], and return the
     /// [`struct@Hash`] of the input. This method is equivalent to
     /// [`finalize_custom`](#method.finalize_custom) with an empty
     /// customization string
+    #[must_use]
     pub fn finalize(self) -> N::Hash {
         self.finalize_custom(&[])
     }
 
     /// Finalize the hash state, consuming the [`Hasher`], and return the
     /// [`struct@Hash`] of the input
-    pub fn finalize_custom(mut self, customization: &[u8]) -> N::Hash {
+    ///
+    /// An empty customization string is the same as no customization string
+    /// at all, so this is equivalent to [`finalize`](#method.finalize) when
+    /// `customization` is empty. The customization string is always followed
+    /// by an encoding of its length, which is what distinguishes it from the
+    /// input
+    ///
+    /// The customization string goes at the end: what KangarooTwelve hashes
+    /// is the input, followed by the customization string, followed by the
+    /// encoding of its length. It isn't a key or prefix absorbed before the
+    /// input, so constructions that expect one should absorb it as input with
+    /// [`new_with_prefix`](#method.new_with_prefix) instead, which produces a
+    /// different [`struct@Hash`]
+    #[must_use]
+    pub fn finalize_custom(self, customization: &[u8]) -> N::Hash {
+        let mut hash = N::Hash::default();
+        self.finalize_custom_into_hash(customization, &mut hash);
+        hash
+    }
+
+    /// Finalize the hash state, consuming the [`Hasher`], and pass the bytes
+    /// of the [`struct@Hash`] of the input to `f`, returning its result. This
+    /// method is equivalent to
+    /// [`finalize_custom_with`](#method.finalize_custom_with) with an empty
+    /// customization string
+    pub fn finalize_with<T>(self, f: impl FnOnce(&[u8]) -> T) -> T {
+        self.finalize_custom_with(&[], f)
+    }
+
+    /// Finalize the hash state, consuming the [`Hasher`], and pass the bytes
+    /// of the [`struct@Hash`] of the input to `f`, returning its result
+    ///
+    /// This is for callers that only need to look at the bytes once (e.g. to
+    /// write them somewhere), as the [`struct@Hash`] doesn't outlive the call
+    pub fn finalize_custom_with<T>(self, customization: &[u8], f: impl FnOnce(&[u8]) -> T) -> T {
+        f(self.finalize_custom(customization).as_slice())
+    }
+
+    /// Finalize the hash state, consuming the [`Hasher`], and write the
+    /// [`struct@Hash`] of the input into `out`. This method is equivalent to
+    /// [`finalize_custom_into_hash`](#method.finalize_custom_into_hash) with
+    /// an empty customization string
+    pub fn finalize_into_hash(self, out: &mut N::Hash) {
+        self.finalize_custom_into_hash(&[], out)
+    }
+
+    /// Finalize the hash state, consuming the [`Hasher`], and write the
+    /// [`struct@Hash`] of the input into `out`, overwriting its previous
+    /// contents
+    ///
+    /// This is equivalent to [`finalize_custom`](#method.finalize_custom),
+    /// but lets a [`struct@Hash`] be reused across many hashes, rather than
+    /// constructing a new one (zeroed) for each of them
+    pub fn finalize_custom_into_hash(self, customization: &[u8], out: &mut N::Hash) {
+        let mut output_reader = self.finalize_custom_xof(customization);
+        unsafe {
+            let ret = marsupial_sys::KangarooTwelve_Squeeze(
+                &mut output_reader.0,
+                out.ptr(),
+                N::Hash::LEN,
+            );
+            debug_assert_eq!(0, ret);
+        }
+    }
+
+    /// Finalize the hash state, consuming the [`Hasher`], and return the
+    /// [`struct@Hash`] of the input, using the concatenation of
+    /// `customization_parts` as the customization string. This is equivalent
+    /// to calling [`finalize_custom`](#method.finalize_custom) with the parts
+    /// joined together
+    ///
+    /// Note that the parts are joined into a temporary buffer before being
+    /// passed to XKCP/K12. The customization string is followed by an
+    /// encoding of its total length, which XKCP/K12 only produces when given
+    /// the whole string at once
+    #[cfg(feature = "alloc")]
+    #[must_use]
+    pub fn finalize_custom_slices(self, customization_parts: &[&[u8]]) -> N::Hash {
+        self.finalize_custom(&customization_parts.concat())
+    }
+
+    /// Finalize the hash state, consuming the [`Hasher`], and return both the
+    /// [`struct@Hash`] of the input and an [`OutputReader`] for any further
+    /// output bytes. This method is equivalent to
+    /// [`digest_and_custom_xof`](#method.digest_and_custom_xof) with an empty
+    /// customization string
+    #[must_use]
+    pub fn digest_and_xof(self) -> (N::Hash, OutputReader) {
+        self.digest_and_custom_xof(&[])
+    }
+
+    /// Finalize the hash state, consuming the [`Hasher`], and return both the
+    /// [`struct@Hash`] of the input and an [`OutputReader`] for any further
+    /// output bytes
+    ///
+    /// The [`struct@Hash`] is the first [`output_len`](#method.output_len)
+    /// bytes of the extended output, and the [`OutputReader`] continues
+    /// immediately after them (its [`position`](OutputReader::position)
+    /// starts at [`output_len`](#method.output_len)). Concatenating the two
+    /// gives the same bytes as squeezing from
+    /// [`finalize_custom_xof`](#method.finalize_custom_xof)
+    #[must_use]
+    pub fn digest_and_custom_xof(self, customization: &[u8]) -> (N::Hash, OutputReader) {
+        let mut output_reader = self.finalize_custom_xof(customization);
         let mut hash = N::Hash::default();
         unsafe {
-            let ret = marsupial_sys::KangarooTwelve_Final(
-                &mut self.0,
-                std::ptr::null_mut(),
-                customization.as_ptr(),
-                customization.len(),
+            let ret = marsupial_sys::KangarooTwelve_Squeeze(
+                &mut output_reader.0,
+                hash.ptr(),
+                N::Hash::LEN,
             );
             debug_assert_eq!(0, ret);
+        }
+        output_reader.1 = N::Hash::LEN as u64;
+        (hash, output_reader)
+    }
+
+    /// Hash `input` with the customization string `customization` all at
+    /// once, returning both its [`struct@Hash`] and an [`OutputReader`] for
+    /// its extended output
+    ///
+    /// The [`struct@Hash`] is a prefix of the extended output: the
+    /// [`OutputReader`] starts at the beginning of the stream, so the first
+    /// [`output_len`](#method.output_len) bytes squeezed from it are the
+    /// bytes of the [`struct@Hash`]. The input is only hashed once. To get a
+    /// reader that continues after the [`struct@Hash`] instead, use
+    /// [`digest_and_custom_xof`](#method.digest_and_custom_xof)
+    #[must_use]
+    pub fn oneshot(input: &[u8], customization: &[u8]) -> (N::Hash, OutputReader) {
+        let mut hasher = Self::new();
+        hasher.update(input);
+        let output_reader = hasher.finalize_custom_xof(customization);
+        let mut instance = output_reader.0;
+        let mut hash = N::Hash::default();
+        unsafe {
             let ret =
-                marsupial_sys::KangarooTwelve_Squeeze(&mut self.0, hash.ptr(), N::Hash::len());
+                marsupial_sys::KangarooTwelve_Squeeze(&mut instance, hash.ptr(), N::Hash::LEN);
             debug_assert_eq!(0, ret);
         }
-        hash
+        (hash, output_reader)
+    }
+
+    /// Finalize the hash state, consuming the [`Hasher`], and compare the
+    /// [`struct@Hash`] of the input against `expected` in constant time. This
+    /// method is equivalent to
+    /// [`finalize_custom_verify`](#method.finalize_custom_verify) with an
+    /// empty customization string
+    ///
+    /// Ignoring the result would skip the verification entirely, so it's
+    /// marked `#[must_use]`, as is every method returning a hash:
+    ///
+    /// ```compile_fail
+    /// # #![deny(unused_must_use)]
+    /// # use marsupial::{Hasher, KT128};
+    /// let mut hasher = Hasher::<KT128>::new();
+    /// hasher.update(b"foo");
+    /// hasher.finalize_verify(&[0; 32]);
+    /// ```
+    #[must_use]
+    pub fn finalize_verify(self, expected: &[u8]) -> bool {
+        self.finalize_custom_verify(&[], expected)
+    }
+
+    /// Finalize the hash state, consuming the [`Hasher`], and compare the
+    /// [`struct@Hash`] of the input against `expected` in constant time.
+    /// If `expected` isn't the length of the canonical [`struct@Hash`], this
+    /// returns `false`
+    #[must_use]
+    pub fn finalize_custom_verify(self, customization: &[u8], expected: &[u8]) -> bool {
+        constant_time_eq::constant_time_eq(self.finalize_custom(customization).as_slice(), expected)
     }
 
     /// Finalize the hash state, consuming the [`Hasher`] and returning
@@ -218,6 +1124,7 @@ where
     /// customization string
     ///
     /// [`OutputReader`]: struct.OutputReader.html
+    #[must_use]
     pub fn finalize_xof(self) -> OutputReader {
         self.finalize_custom_xof(&[])
     }
@@ -225,18 +1132,60 @@ where
     /// Finalize the hash state, consuming the [`Hasher`] and returning an
     /// [`OutputReader`], which can supply any number of output bytes
     ///
+    /// # Panics
+    ///
+    /// Panics if XKCP/K12 fails to finalize the hash state, which only happens
+    /// if it has already been finalized. See
+    /// [`try_finalize_custom_xof`](#method.try_finalize_custom_xof) for a
+    /// version of this method that returns the error instead
+    ///
     /// [`OutputReader`]: struct.OutputReader.html
-    pub fn finalize_custom_xof(mut self, customization: &[u8]) -> OutputReader {
-        unsafe {
-            let ret = marsupial_sys::KangarooTwelve_Final(
+    #[must_use]
+    pub fn finalize_custom_xof(self, customization: &[u8]) -> OutputReader {
+        self.try_finalize_custom_xof(customization)
+            .expect("this instance has already been finalized")
+    }
+
+    /// Finalize the hash state, consuming the [`Hasher`] and returning an
+    /// [`OutputReader`], or the [`K12Error`] XKCP/K12 reported if it failed
+    /// to finalize the hash state. This method is equivalent to
+    /// [`try_finalize_custom_xof`](#method.try_finalize_custom_xof) with an
+    /// empty customization string
+    pub fn try_finalize_xof(self) -> Result<OutputReader, K12Error> {
+        self.try_finalize_custom_xof(&[])
+    }
+
+    /// Finalize the hash state, consuming the [`Hasher`] and returning an
+    /// [`OutputReader`], or the [`K12Error`] XKCP/K12 reported if it failed
+    /// to finalize the hash state
+    pub fn try_finalize_custom_xof(
+        mut self,
+        customization: &[u8],
+    ) -> Result<OutputReader, K12Error> {
+        //NOTE: the pointer of an empty slice is dangling, but never null, and
+        //      XKCP/K12 doesn't read through it when the length is zero
+        let ret = unsafe {
+            marsupial_sys::KangarooTwelve_Final(
                 &mut self.0,
-                std::ptr::null_mut(),
+                ptr::null_mut(),
                 customization.as_ptr(),
                 customization.len(),
-            );
-            debug_assert_eq!(0, ret);
+            )
+        };
+        if ret != 0 {
+            return Err(K12Error::new(ret));
         }
-        OutputReader(self.0)
+        Ok(OutputReader(self.0, 0))
+    }
+}
+
+//NOTE: this isn't derived, as that would require `N: Clone`
+impl<N> Clone for Hasher<N>
+where
+    N: SecurityLevel,
+{
+    fn clone(&self) -> Self {
+        Self(self.0, PhantomData)
     }
 }
 
@@ -258,6 +1207,42 @@ where
     }
 }
 
+/// Absorbs everything written into the hash state, so a [`Hasher`] can be
+/// the destination of [`std::io::copy`]. Writes never fail, and always
+/// consume the whole buffer
+///
+/// `&mut Hasher<N>` implements [`Write`](std::io::Write) too, through the
+/// standard library's implementation for mutable references to writers, so
+/// the [`Hasher`] doesn't need to be given up:
+///
+/// ```
+/// # use marsupial::{Hasher, KT128};
+/// # use std::io::{self, Write};
+/// fn write_greeting(mut writer: impl Write) -> io::Result<()> {
+///     writer.write_all(b"hello")
+/// }
+///
+/// let mut hasher = Hasher::<KT128>::new();
+/// write_greeting(&mut hasher).unwrap();
+/// assert_eq!(hasher.finalize(), marsupial::hash::<KT128>(b"hello"));
+/// ```
+#[cfg(feature = "std")]
+impl<N> std::io::Write for Hasher<N>
+where
+    N: SecurityLevel,
+{
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.update(buf);
+        Ok(buf.len())
+    }
+
+    #[inline]
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
 /// An output of the default size, 32 bytes, which provides constant-time
 /// equality checking
 ///
@@ -269,18 +1254,16 @@ where
 /// conversion happens implicitly and the constant-time property is
 /// accidentally lost
 ///
-/// `Hash` provides the [`to_hex`] method for converting to hexadecimal. It
-/// doesn't directly support converting from hexadecimal, but here's an
-/// example of doing that with the [`hex`] crate:
+/// `Hash` provides the [`to_hex`] method for converting to hexadecimal, and
+/// the [`from_hex`] method (also used by its [`FromStr`] implementation) for
+/// converting from it:
 ///
 /// ```
 /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
 /// # use marsupial::Hash;
-/// # use std::convert::TryInto;
 /// let hash_hex = "d74981efa70a0c880b8d8c1985d075dbcbf679b99a5f9914e5aaf96b831a9e24";
-/// let hash_bytes = hex::decode(hash_hex)?;
-/// let hash_array: [u8; 32] = hash_bytes[..].try_into()?;
-/// let hash: Hash<32> = hash_array.into();
+/// let hash: Hash<32> = hash_hex.parse()?;
+/// assert_eq!(hash, Hash::from_hex(hash_hex)?);
 /// # Ok(())
 /// # }
 /// ```
@@ -291,11 +1274,15 @@ where
 /// [`Deref`]: https://doc.rust-lang.org/stable/std/ops/trait.Deref.html
 /// [`AsRef`]: https://doc.rust-lang.org/std/convert/trait.AsRef.html
 /// [`to_hex`]: #method.to_hex
-/// [`hex`]: https://crates.io/crates/hex
+/// [`from_hex`]: #method.from_hex
+/// [`FromStr`]: https://doc.rust-lang.org/std/str/trait.FromStr.html
 //NOTE: this is fine because our manual `PartialEq` implementation doesn't
-//      deviate from how rust would determine equality normally
+//      deviate from how rust would determine equality normally. the derived
+//      `Hash` implementation only feeds the inner `[u8; N]` to the hasher, so
+//      equal hashes always produce equal std hashes
 #[allow(clippy::derived_hash_with_manual_eq)]
 #[derive(Clone, Copy, Hash)]
+#[cfg_attr(feature = "rkyv", repr(transparent))]
 pub struct Hash<const N: usize>([u8; N]);
 
 impl<const N: usize> Hash<N> {
@@ -303,9 +1290,319 @@ impl<const N: usize> Hash<N> {
     /// constant-time equality checking, so if  you need to compare hashes,
     /// prefer the [`struct@Hash`] type
     #[inline]
+    #[must_use]
     pub fn as_bytes(&self) -> &[u8; N] {
         &self.0
     }
+
+    /// Interpret the bytes of the [`struct@Hash`] as `M` little-endian `u64`
+    /// lanes, where lane `i` is made up of bytes `8 * i` to `8 * i + 7`.
+    /// `M` must be `N / 8`, which is checked at compile time, so this is
+    /// `as_u64_le::<4>` for KT128 and `as_u64_le::<8>` for KT256
+    ///
+    /// KangarooTwelve's output is a string of bytes, with no endianness of its
+    /// own, so this is only a convention for callers that need integers, such
+    /// as hash table keys
+    ///
+    /// ```
+    /// # use marsupial::KT128;
+    /// let lanes: [u64; 4] = marsupial::hash::<KT128>(b"foo").as_u64_le();
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn as_u64_le<const M: usize>(&self) -> [u64; M] {
+        self.as_u64(u64::from_le_bytes)
+    }
+
+    /// Interpret the bytes of the [`struct@Hash`] as `M` big-endian `u64`
+    /// lanes. This is the same as [`as_u64_le`](#method.as_u64_le), except
+    /// for the byte order within each lane
+    #[inline]
+    #[must_use]
+    pub fn as_u64_be<const M: usize>(&self) -> [u64; M] {
+        self.as_u64(u64::from_be_bytes)
+    }
+
+    #[inline]
+    fn as_u64<const M: usize>(&self, from_bytes: fn([u8; 8]) -> u64) -> [u64; M] {
+        const { assert!(M * 8 == N, "a hash of N bytes has N / 8 u64 lanes") };
+        let mut lanes = [0; M];
+        for (lane, bytes) in lanes.iter_mut().zip(self.0.chunks_exact(8)) {
+            let mut lane_bytes = [0; 8];
+            lane_bytes.copy_from_slice(bytes);
+            *lane = from_bytes(lane_bytes);
+        }
+        lanes
+    }
+
+    /// Keep only the first `M` bytes of the [`struct@Hash`]. `M` must be at
+    /// most `N`, which is checked at compile time
+    ///
+    /// As KangarooTwelve is an extendable output function, this is the same
+    /// as squeezing `M` bytes to begin with. To derive a shorter value that
+    /// depends on every byte of the [`struct@Hash`] instead, see
+    /// [`fold`](#method.fold)
+    ///
+    /// ```
+    /// # use marsupial::{Hash, KT128};
+    /// let short: Hash<16> = marsupial::hash::<KT128>(b"foo").truncate();
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn truncate<const M: usize>(&self) -> Hash<M> {
+        const { assert!(M <= N, "a hash can't be truncated to a longer length") };
+        let mut bytes = [0; M];
+        bytes.copy_from_slice(&self.0[..M]);
+        Hash(bytes)
+    }
+
+    #[inline]
+    fn fold_with<S, const M: usize>(&self) -> Hash<M>
+    where
+        S: SecurityLevel,
+    {
+        let mut bytes = [0; M];
+        let mut hasher = Hasher::<S>::new();
+        hasher.update(self.0);
+        hasher.finalize_xof().squeeze(&mut bytes);
+        Hash(bytes)
+    }
+
+    /// Move the bytes of the [`struct@Hash`] into a boxed slice of length `N`
+    #[cfg(feature = "alloc")]
+    #[inline]
+    #[must_use]
+    pub fn into_boxed_slice(self) -> Box<[u8]> {
+        Box::new(self.0)
+    }
+
+    /// Encode the [`struct@Hash`] as a lowercase hexadecimal string
+    #[cfg(feature = "alloc")]
+    #[must_use]
+    pub fn to_hex(&self) -> String {
+        encode_hex(&self.0, LOWER_HEX_DIGITS, None)
+    }
+
+    /// Encode the [`struct@Hash`] as an uppercase hexadecimal string
+    #[cfg(feature = "alloc")]
+    #[must_use]
+    pub fn to_hex_upper(&self) -> String {
+        encode_hex(&self.0, UPPER_HEX_DIGITS, None)
+    }
+
+    /// Encode the [`struct@Hash`] as a lowercase hexadecimal string, with
+    /// `separator` inserted between every `group` hexadecimal digits. For
+    /// example, a `group` of 2 with a `separator` of `':'` produces
+    /// `aa:bb:cc:...`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `group` is zero
+    #[cfg(feature = "alloc")]
+    #[must_use]
+    pub fn to_hex_separated(&self, separator: char, group: usize) -> String {
+        assert_ne!(group, 0, "the group size must be nonzero");
+        encode_hex(&self.0, LOWER_HEX_DIGITS, Some((separator, group)))
+    }
+
+    /// Encode the [`struct@Hash`] as lowercase hexadecimal ASCII digits, in
+    /// an array rather than a `String`. `M` must be `2 * N`, which is
+    /// checked at compile time, so this is `to_hex_bytes::<64>` for KT128 and
+    /// `to_hex_bytes::<128>` for KT256
+    ///
+    /// This doesn't allocate, so it's available without the `alloc` feature:
+    ///
+    /// ```
+    /// # use marsupial::KT128;
+    /// let hex: [u8; 64] = marsupial::hash::<KT128>(b"").to_hex_bytes();
+    /// assert_eq!(
+    ///     core::str::from_utf8(&hex),
+    ///     Ok("1ac2d450fc3b4205d19da7bfca1b37513c0803577ac7167f06fe2ce1f0ef39e5")
+    /// );
+    /// ```
+    #[must_use]
+    pub fn to_hex_bytes<const M: usize>(&self) -> [u8; M] {
+        const { assert!(M == 2 * N, "a hash of N bytes has 2 * N hexadecimal digits") };
+        let mut hex = [0; M];
+        for (digits, byte) in hex.chunks_exact_mut(2).zip(self.0) {
+            digits[0] = LOWER_HEX_DIGITS[(byte >> 4) as usize];
+            digits[1] = LOWER_HEX_DIGITS[(byte & 0xf) as usize];
+        }
+        hex
+    }
+
+    /// Decode a [`struct@Hash`] from a hexadecimal string. Both lowercase and
+    /// uppercase digits are accepted
+    pub fn from_hex(hex: &str) -> Result<Self, HashParseError> {
+        let hex = hex.as_bytes();
+        if hex.len() % 2 == 1 || !hex.iter().all(u8::is_ascii_hexdigit) {
+            return Err(HashParseError::InvalidEncoding);
+        }
+        if hex.len() != N * 2 {
+            return Err(HashParseError::InvalidLength {
+                expected: N,
+                actual: hex.len() / 2,
+            });
+        }
+
+        let nibble = |digit: u8| match digit {
+            b'0'..=b'9' => digit - b'0',
+            b'a'..=b'f' => digit - b'a' + 10,
+            _ => digit - b'A' + 10,
+        };
+        let mut bytes = [0; N];
+        for (byte, digits) in bytes.iter_mut().zip(hex.chunks_exact(2)) {
+            *byte = (nibble(digits[0]) << 4) | nibble(digits[1]);
+        }
+        Ok(Self(bytes))
+    }
+
+    /// Decode a [`struct@Hash`] from a string in any of the supported
+    /// encodings, which are tried in order:
+    ///
+    /// 1. hexadecimal, as accepted by [`from_hex`](#method.from_hex)
+    /// 2. standard base64 (with the `base64` feature)
+    /// 3. URL-safe base64 (with the `base64` feature)
+    ///
+    /// Padding is optional for both base64 alphabets. Base64 is only tried if
+    /// the string contains a character that can't appear in hexadecimal, so
+    /// malformed hexadecimal (such as a digit too few) is reported as such,
+    /// rather than being decoded as base64 into some other hash
+    ///
+    /// If the string can't be decoded in any of them, the error from
+    /// decoding it as hexadecimal is returned
+    pub fn parse_any(s: &str) -> Result<Self, HashParseError> {
+        let hex_error = match Self::from_hex(s) {
+            Ok(hash) => return Ok(hash),
+            Err(e) => e,
+        };
+
+        #[cfg(feature = "base64")]
+        if !s.bytes().all(|c| c.is_ascii_hexdigit()) {
+            use base64::{
+                alphabet,
+                engine::{DecodePaddingMode, GeneralPurpose, GeneralPurposeConfig},
+                Engine,
+            };
+
+            let config = GeneralPurposeConfig::new()
+                .with_decode_padding_mode(DecodePaddingMode::Indifferent);
+            for engine in [
+                GeneralPurpose::new(&alphabet::STANDARD, config),
+                GeneralPurpose::new(&alphabet::URL_SAFE, config),
+            ] {
+                if let Ok(bytes) = engine.decode(s) {
+                    return Self::try_from(bytes).map_err(|e| HashParseError::InvalidLength {
+                        expected: e.expected(),
+                        actual: e.actual(),
+                    });
+                }
+            }
+        }
+
+        Err(hex_error)
+    }
+
+    /// Compare two [`struct@Hash`]es in constant time. This is what the
+    /// [`PartialEq`] implementation uses
+    #[inline]
+    #[must_use]
+    pub fn ct_eq(&self, other: &Hash<N>) -> bool {
+        constant_time_eq::constant_time_eq_n(&self.0, &other.0)
+    }
+
+    /// Check whether every byte of the [`struct@Hash`] is zero, in constant
+    /// time. As [`Hash::default`] is all zeros, this distinguishes a
+    /// placeholder from a real digest, which is all zeros with negligible
+    /// probability
+    #[inline]
+    #[must_use]
+    pub fn is_zero(&self) -> bool {
+        constant_time_eq::constant_time_eq_n(&self.0, &[0; N])
+    }
+
+    /// Select `a` if `choice` is `true`, or `b` if it is `false`, without
+    /// branching on `choice` or on the contents of either [`struct@Hash`]
+    ///
+    /// This only helps if `choice` is itself computed in constant time, such
+    /// as from [`ct_eq`](#method.ct_eq). Branching on a secret to produce it
+    /// leaks that secret regardless
+    #[inline]
+    #[must_use]
+    pub fn conditional_select(a: &Hash<N>, b: &Hash<N>, choice: bool) -> Hash<N> {
+        //NOTE: `black_box` keeps the optimizer from turning the mask back
+        //      into a branch on `choice`
+        let mask = core::hint::black_box((choice as u8).wrapping_neg());
+        let mut selected = [0; N];
+        for ((selected, a), b) in selected.iter_mut().zip(&a.0).zip(&b.0) {
+            *selected = b ^ (mask & (a ^ b));
+        }
+        Hash(selected)
+    }
+
+    /// Compare two [`struct@Hash`]es *without* constant-time guarantees.
+    /// This is faster than [`ct_eq`](#method.ct_eq), but must only be used
+    /// when neither hash is secret, such as when deduplicating public content
+    /// addresses
+    #[inline]
+    #[must_use]
+    pub fn fast_eq(&self, other: &Hash<N>) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl Hash<32> {
+    /// Derive an `M` byte value from the whole [`struct@Hash`], by hashing
+    /// it again with KT128 and squeezing `M` bytes of output
+    ///
+    /// Unlike [`truncate`](#method.truncate), which discards all but the
+    /// first `M` bytes, every byte of the [`struct@Hash`] affects the result
+    ///
+    /// ```
+    /// # use marsupial::{Hash, KT128};
+    /// let digest = marsupial::hash::<KT128>(b"foo");
+    /// let tag: Hash<16> = digest.fold();
+    /// assert_eq!(tag, marsupial::hash::<KT128>(digest.as_bytes()).truncate());
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn fold<const M: usize>(&self) -> Hash<M> {
+        self.fold_with::<KT128, M>()
+    }
+}
+
+impl Hash<64> {
+    /// Derive an `M` byte value from the whole [`struct@Hash`], by hashing
+    /// it again with KT256 and squeezing `M` bytes of output
+    ///
+    /// Unlike [`truncate`](#method.truncate), which discards all but the
+    /// first `M` bytes, every byte of the [`struct@Hash`] affects the result
+    #[inline]
+    #[must_use]
+    pub fn fold<const M: usize>(&self) -> Hash<M> {
+        self.fold_with::<KT256, M>()
+    }
+}
+
+/// This implementation is equivalent to [`Hash::from_hex`]
+impl<const N: usize> core::str::FromStr for Hash<N> {
+    type Err = HashParseError;
+
+    #[inline]
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_hex(s)
+    }
+}
+
+/// Equivalent to [`Hash::from_hex`], like the [`FromStr`](core::str::FromStr)
+/// implementation
+impl<const N: usize> TryFrom<&str> for Hash<N> {
+    type Error = HashParseError;
+
+    #[inline]
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        Self::from_hex(s)
+    }
 }
 
 impl<const N: usize> From<[u8; N]> for Hash<N> {
@@ -315,6 +1612,33 @@ impl<const N: usize> From<[u8; N]> for Hash<N> {
     }
 }
 
+impl<const N: usize> TryFrom<&[u8]> for Hash<N> {
+    type Error = TryFromSliceError;
+
+    #[inline]
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        <[u8; N]>::try_from(bytes)
+            .map(Self)
+            .map_err(|_| TryFromSliceError::new(N, bytes.len()))
+    }
+}
+
+/// This implementation consumes the [`Vec`], moving its bytes into the
+/// [`struct@Hash`] if it has exactly `N` of them
+#[cfg(feature = "alloc")]
+impl<const N: usize> TryFrom<Vec<u8>> for Hash<N> {
+    type Error = TryFromSliceError;
+
+    #[inline]
+    fn try_from(bytes: Vec<u8>) -> Result<Self, Self::Error> {
+        let actual = bytes.len();
+        <[u8; N]>::try_from(bytes)
+            .map(Self)
+            .map_err(|_| TryFromSliceError::new(N, actual))
+    }
+}
+
+#[cfg(feature = "alloc")]
 impl<const N: usize> From<Hash<N>> for Vec<u8> {
     #[inline]
     fn from(hash: Hash<N>) -> Self {
@@ -333,7 +1657,7 @@ impl<const N: usize> From<Hash<N>> for [u8; N] {
 impl<const N: usize> PartialEq for Hash<N> {
     #[inline]
     fn eq(&self, other: &Hash<N>) -> bool {
-        constant_time_eq::constant_time_eq_n(&self.0, &other.0)
+        self.ct_eq(other)
     }
 }
 
@@ -359,6 +1683,110 @@ impl<const N: usize> Default for Hash<N> {
     }
 }
 
+#[cfg(feature = "digest")]
+impl From<Hash<32>> for digest::generic_array::GenericArray<u8, digest::typenum::U32> {
+    #[inline]
+    fn from(hash: Hash<32>) -> Self {
+        hash.0.into()
+    }
+}
+
+#[cfg(feature = "digest")]
+impl From<digest::generic_array::GenericArray<u8, digest::typenum::U32>> for Hash<32> {
+    #[inline]
+    fn from(bytes: digest::generic_array::GenericArray<u8, digest::typenum::U32>) -> Self {
+        Self(bytes.into())
+    }
+}
+
+#[cfg(feature = "digest")]
+impl From<Hash<64>> for digest::generic_array::GenericArray<u8, digest::typenum::U64> {
+    #[inline]
+    fn from(hash: Hash<64>) -> Self {
+        hash.0.into()
+    }
+}
+
+#[cfg(feature = "digest")]
+impl From<digest::generic_array::GenericArray<u8, digest::typenum::U64>> for Hash<64> {
+    #[inline]
+    fn from(bytes: digest::generic_array::GenericArray<u8, digest::typenum::U64>) -> Self {
+        Self(bytes.into())
+    }
+}
+
+/// This implementation fills the [`struct@Hash`] with bytes taken directly
+/// from the unstructured input
+#[cfg(feature = "arbitrary")]
+impl<'a, const N: usize> arbitrary::Arbitrary<'a> for Hash<N> {
+    #[inline]
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        u.arbitrary().map(Self)
+    }
+
+    #[inline]
+    fn size_hint(depth: usize) -> (usize, Option<usize>) {
+        <[u8; N] as arbitrary::Arbitrary<'a>>::size_hint(depth)
+    }
+}
+
+//NOTE: a `Hash` is just its bytes, so it is its own archived form, and any
+//      `N` bytes are a valid one
+#[cfg(feature = "rkyv")]
+unsafe impl<const N: usize> rkyv::Portable for Hash<N> {}
+
+#[cfg(feature = "rkyv")]
+unsafe impl<C, const N: usize> rkyv::bytecheck::CheckBytes<C> for Hash<N>
+where
+    C: rkyv::rancor::Fallible + ?Sized,
+{
+    #[inline]
+    unsafe fn check_bytes(_: *const Self, _: &mut C) -> Result<(), C::Error> {
+        Ok(())
+    }
+}
+
+/// The archived form of a [`struct@Hash`] is the [`struct@Hash`] itself, so
+/// it can be accessed in an archive without any copying, and has the same
+/// bytes as [`as_bytes`](Hash::as_bytes)
+#[cfg(feature = "rkyv")]
+impl<const N: usize> rkyv::Archive for Hash<N> {
+    const COPY_OPTIMIZATION: rkyv::traits::CopyOptimization<Self> =
+        unsafe { rkyv::traits::CopyOptimization::enable() };
+
+    type Archived = Self;
+    type Resolver = ();
+
+    #[inline]
+    fn resolve(&self, _: Self::Resolver, out: rkyv::Place<Self::Archived>) {
+        //NOTE: a `Hash` has no padding, so none of its bytes are
+        //      uninitialized
+        unsafe { out.write_unchecked(*self) }
+    }
+}
+
+#[cfg(feature = "rkyv")]
+impl<S, const N: usize> rkyv::Serialize<S> for Hash<N>
+where
+    S: rkyv::rancor::Fallible + ?Sized,
+{
+    #[inline]
+    fn serialize(&self, _: &mut S) -> Result<Self::Resolver, S::Error> {
+        Ok(())
+    }
+}
+
+#[cfg(feature = "rkyv")]
+impl<D, const N: usize> rkyv::Deserialize<Hash<N>, D> for Hash<N>
+where
+    D: rkyv::rancor::Fallible + ?Sized,
+{
+    #[inline]
+    fn deserialize(&self, _: &mut D) -> Result<Hash<N>, D::Error> {
+        Ok(*self)
+    }
+}
+
 impl<const N: usize> HashContainer for Hash<N> {
     #[inline]
     fn ptr(&mut self) -> *mut u8 {
@@ -366,46 +1794,345 @@ impl<const N: usize> HashContainer for Hash<N> {
     }
 
     #[inline]
-    fn len() -> usize {
-        N
+    fn as_slice(&self) -> &[u8] {
+        &self.0
     }
+
+    const LEN: usize = N;
 }
 
 /// An incremental reader for extended output, returned by
 /// [`Hasher::finalize_xof`](struct.Hasher.html#method.finalize_xof) and
 /// [`Hasher::finalize_custom_xof`](struct.Hasher.html#method.finalize_custom_xof)
+///
+/// Like [`Hasher`], [`OutputReader`] is both [`Send`] and [`Sync`]
 #[derive(Clone)]
-pub struct OutputReader(marsupial_sys::KangarooTwelve_Instance);
+pub struct OutputReader(marsupial_sys::KangarooTwelve_Instance, u64);
 
 impl OutputReader {
+    /// The version of the layout produced by
+    /// [`to_raw_state`](#method.to_raw_state)
+    const RAW_STATE_VERSION: u8 = 2;
+
+    /// The length of the state of a Keccak-p\[1600\] permutation (in bytes)
+    const SPONGE_STATE_LEN: usize = 200;
+
+    /// The length of a serialized TurboSHAKE instance (in bytes): its state,
+    /// followed by its rate, byte index, and squeezing flag as little-endian
+    /// `u32`s
+    const RAW_SPONGE_LEN: usize = Self::SPONGE_STATE_LEN + 3 * 4;
+
+    /// The length of the raw state produced by
+    /// [`to_raw_state`](#method.to_raw_state) (in bytes)
+    pub const RAW_STATE_LEN: usize = 1 + 8 + 2 * Self::RAW_SPONGE_LEN + 2 * 8 + 3 * 4;
+
+    /// Snapshot the state of the [`OutputReader`], so that squeezing can be
+    /// resumed later with [`from_raw_state`](#method.from_raw_state)
+    ///
+    /// The layout consists of a version byte, the position of the
+    /// [`OutputReader`] as a little-endian `u64`, and each field of the
+    /// underlying XKCP instance in declaration order: the queue and final
+    /// node sponges (each as its state bytes, then its rate, byte index, and
+    /// squeezing flag), then the fixed output length and block number as
+    /// `u64`s, and the queue's absorbed length, phase, and security level as
+    /// `u32`s, all little-endian. It is only meaningful to the same version of
+    /// XKCP/K12 and this crate. Note that the state is as secret as the output
+    /// it produces
+    #[cfg(feature = "alloc")]
+    #[allow(clippy::unnecessary_cast)]
+    pub fn to_raw_state(&self) -> Vec<u8> {
+        //NOTE: the fields are written one by one, rather than by viewing the
+        //      instance as bytes, as its padding bytes are uninitialized after
+        //      any typed copy of it. the casts are kept even where they're
+        //      no-ops, as the field types depend on the generated bindings
+        fn push_sponge(state: &mut Vec<u8>, sponge: &marsupial_sys::TurboSHAKE_Instance) {
+            state.extend_from_slice(&sponge.state);
+            state.extend_from_slice(&(sponge.rate as u32).to_le_bytes());
+            state.extend_from_slice(&(sponge.byteIOIndex as u32).to_le_bytes());
+            state.extend_from_slice(&(sponge.squeezing as u32).to_le_bytes());
+        }
+
+        let instance = &self.0;
+        let mut state = Vec::with_capacity(Self::RAW_STATE_LEN);
+        state.push(Self::RAW_STATE_VERSION);
+        state.extend_from_slice(&self.1.to_le_bytes());
+        push_sponge(&mut state, &instance.queueNode);
+        push_sponge(&mut state, &instance.finalNode);
+        state.extend_from_slice(&(instance.fixedOutputLength as u64).to_le_bytes());
+        state.extend_from_slice(&(instance.blockNumber as u64).to_le_bytes());
+        state.extend_from_slice(&(instance.queueAbsorbedLen as u32).to_le_bytes());
+        state.extend_from_slice(&(instance.phase as u32).to_le_bytes());
+        state.extend_from_slice(&(instance.securityLevel as u32).to_le_bytes());
+        debug_assert_eq!(state.len(), Self::RAW_STATE_LEN);
+        state
+    }
+
+    /// Reconstruct an [`OutputReader`] from a state previously produced by
+    /// [`to_raw_state`](#method.to_raw_state). Squeezing from it continues
+    /// from the position the snapshot was taken at
+    ///
+    /// # Safety
+    ///
+    /// `state` must have been produced by
+    /// [`to_raw_state`](#method.to_raw_state), using the same version of this
+    /// crate. The layout is not validated beyond its length and version, and
+    /// XKCP/K12 trusts the indices it stores
+    ///
+    /// # Panics
+    ///
+    /// Panics if `state` is not [`RAW_STATE_LEN`](#associatedconstant.RAW_STATE_LEN)
+    /// bytes long, or if it has an unknown layout version
+    pub unsafe fn from_raw_state(state: &[u8]) -> Self {
+        fn take<const M: usize>(state: &mut &[u8]) -> [u8; M] {
+            let (bytes, rest) = state.split_at(M);
+            *state = rest;
+            let mut array = [0; M];
+            array.copy_from_slice(bytes);
+            array
+        }
+
+        fn take_u32(state: &mut &[u8]) -> u32 {
+            u32::from_le_bytes(take(state))
+        }
+
+        fn take_u64(state: &mut &[u8]) -> u64 {
+            u64::from_le_bytes(take(state))
+        }
+
+        fn take_sponge(state: &mut &[u8]) -> marsupial_sys::TurboSHAKE_Instance {
+            marsupial_sys::TurboSHAKE_Instance {
+                state: take::<{ OutputReader::SPONGE_STATE_LEN }>(state),
+                rate: take_u32(state) as _,
+                byteIOIndex: take_u32(state) as _,
+                squeezing: take_u32(state) as _,
+            }
+        }
+
+        assert_eq!(
+            state.len(),
+            Self::RAW_STATE_LEN,
+            "the raw state has an incorrect length"
+        );
+        assert_eq!(
+            state[0],
+            Self::RAW_STATE_VERSION,
+            "the raw state has an unknown layout version"
+        );
+
+        let mut state = &state[1..];
+        let position = take_u64(&mut state);
+        let instance = marsupial_sys::KangarooTwelve_Instance {
+            queueNode: take_sponge(&mut state),
+            finalNode: take_sponge(&mut state),
+            fixedOutputLength: take_u64(&mut state) as usize,
+            blockNumber: take_u64(&mut state) as usize,
+            queueAbsorbedLen: take_u32(&mut state) as _,
+            phase: take_u32(&mut state) as _,
+            securityLevel: take_u32(&mut state) as _,
+        };
+        Self(instance, position)
+    }
+
+    /// The number of output bytes that have been squeezed from the
+    /// [`OutputReader`] so far
+    #[inline]
+    pub fn position(&self) -> u64 {
+        self.1
+    }
+
     /// Fill a buffer with output bytes and advance the position of the
     /// [`OutputReader`]
     ///
     /// This is equivalent to [`Read::read`], except that it
     /// doesn't return a `Result`. Both methods always fill the entire buffer
     ///
+    /// Unlike absorbing input, squeezing output can't make use of the
+    /// parallel permutations in the SIMD implementations: every
+    /// [`Hasher::RATE`] bytes of output are produced by permuting the state
+    /// that produced the previous ones, so large outputs are always generated
+    /// serially
+    ///
     /// [`Read::read`]: #method.read
     pub fn squeeze(&mut self, buf: &mut [u8]) {
-        debug_assert_eq!(self.0.phase, 3, "this instance has not yet been finalized");
+        //NOTE: `KangarooTwelve_Squeeze` fails without writing anything when
+        //      the instance isn't squeezing, so this is checked in release
+        //      builds as well
+        assert_eq!(self.0.phase, 3, "this instance has not yet been finalized");
+        //NOTE: squeezing zero bytes is a no-op in xkcp/k12, which never reads
+        //      through the (dangling, for an empty slice) pointer in that
+        //      case. it's short-circuited anyway, so that this doesn't depend
+        //      on that
+        if buf.is_empty() {
+            return;
+        }
         unsafe {
             let ret =
                 marsupial_sys::KangarooTwelve_Squeeze(&mut self.0, buf.as_mut_ptr(), buf.len());
             debug_assert_eq!(0, ret);
         }
+        self.1 += buf.len() as u64;
+    }
+
+    /// Consume the [`OutputReader`], returning an iterator over its output
+    /// in blocks of `M` bytes. The iterator never ends, so it should be
+    /// bounded with e.g. [`Iterator::take`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use marsupial::{KT128, Hasher};
+    /// let mut hasher = Hasher::<KT128>::new();
+    /// hasher.update(b"foobarbaz");
+    /// let blocks: Vec<[u8; 16]> = hasher.finalize_xof().block_iter().take(3).collect();
+    /// assert_eq!(blocks.len(), 3);
+    /// ```
+    pub fn block_iter<const M: usize>(mut self) -> impl Iterator<Item = [u8; M]> {
+        core::iter::from_fn(move || {
+            let mut block = [0; M];
+            self.squeeze(&mut block);
+            Some(block)
+        })
+    }
+
+    /// Squeeze `len` bytes from both `a` and `b`, and return whether they
+    /// were equal. The output is compared in chunks as it's squeezed, and
+    /// squeezing stops at the first chunk that differs, leaving the positions
+    /// of the [`OutputReader`]s somewhere before `len` bytes ahead
+    ///
+    /// This is meant for tests, such as checking that two independently
+    /// derived output streams agree over a large length without buffering
+    /// them entirely. It is *not* constant-time, so it must not be used to
+    /// compare secret output
+    #[must_use]
+    pub fn streams_equal(a: &mut OutputReader, b: &mut OutputReader, mut len: usize) -> bool {
+        let mut a_chunk = [0; SCRATCH_LEN];
+        let mut b_chunk = [0; SCRATCH_LEN];
+        while len > 0 {
+            let chunk_len = len.min(a_chunk.len());
+            a.squeeze(&mut a_chunk[..chunk_len]);
+            b.squeeze(&mut b_chunk[..chunk_len]);
+            if a_chunk[..chunk_len] != b_chunk[..chunk_len] {
+                return false;
+            }
+            len -= chunk_len;
+        }
+        true
+    }
+
+    /// Advance the position of the [`OutputReader`] by `n` bytes, discarding
+    /// the output bytes skipped over
+    ///
+    /// The skipped bytes still have to be generated, so this takes as long
+    /// as squeezing them would, but it doesn't need a buffer large enough to
+    /// hold them
+    pub fn discard(&mut self, mut n: u64) {
+        let mut scratch = [MaybeUninit::uninit(); SCRATCH_LEN];
+        while n > 0 {
+            let len = n.min(scratch.len() as u64) as usize;
+            self.squeeze_uninit(&mut scratch[..len]);
+            n -= len as u64;
+        }
+    }
+
+    /// XOR the next `buf.len()` output bytes into `buf` in place, and advance
+    /// the position of the [`OutputReader`]
+    ///
+    /// This applies the output as a keystream. Doing it again from the same
+    /// position restores the original contents of `buf`:
+    ///
+    /// ```
+    /// # use marsupial::{KT128, Hasher};
+    /// let mut hasher = Hasher::<KT128>::new();
+    /// hasher.update(b"key and nonce");
+    /// let output_reader = hasher.finalize_xof();
+    ///
+    /// let mut data = *b"foobarbaz";
+    /// output_reader.clone().squeeze_xor(&mut data);
+    /// assert_ne!(&data, b"foobarbaz");
+    /// output_reader.clone().squeeze_xor(&mut data);
+    /// assert_eq!(&data, b"foobarbaz");
+    /// ```
+    ///
+    /// Note that this provides confidentiality at most. Nothing detects
+    /// modifications of the data, and reusing the same output for different
+    /// data reveals their XOR
+    pub fn squeeze_xor(&mut self, buf: &mut [u8]) {
+        let mut scratch = [MaybeUninit::uninit(); SCRATCH_LEN];
+        for chunk in buf.chunks_mut(SCRATCH_LEN) {
+            let keystream = self.squeeze_uninit(&mut scratch[..chunk.len()]);
+            for (byte, key) in chunk.iter_mut().zip(keystream.iter()) {
+                *byte ^= key;
+            }
+        }
+    }
+
+    /// Fill a buffer of possibly uninitialized memory with output bytes,
+    /// advance the position of the [`OutputReader`], and return the now
+    /// initialized buffer
+    ///
+    /// This is equivalent to [`squeeze`](#method.squeeze), except that the
+    /// buffer doesn't need to be initialized (e.g. zeroed) beforehand
+    pub fn squeeze_uninit<'a>(&mut self, buf: &'a mut [MaybeUninit<u8>]) -> &'a mut [u8] {
+        assert_eq!(self.0.phase, 3, "this instance has not yet been finalized");
+        if buf.is_empty() {
+            return &mut [];
+        }
+        unsafe {
+            let ret = marsupial_sys::KangarooTwelve_Squeeze(
+                &mut self.0,
+                buf.as_mut_ptr().cast(),
+                buf.len(),
+            );
+            debug_assert_eq!(0, ret);
+            self.1 += buf.len() as u64;
+
+            //NOTE: this is sound because `KangarooTwelve_Squeeze` always
+            //      writes every one of the `buf.len()` bytes it is given
+            &mut *(buf as *mut [MaybeUninit<u8>] as *mut [u8])
+        }
+    }
+
+    /// Extend `out` with the next `additional` output bytes. This is
+    /// equivalent to resizing `out` and calling [`squeeze`](#method.squeeze)
+    /// on the new bytes, but they aren't zeroed first
+    #[cfg(feature = "alloc")]
+    pub fn squeeze_append(&mut self, out: &mut Vec<u8>, additional: usize) {
+        out.reserve(additional);
+        let len = out.len();
+        self.squeeze_uninit(&mut out.spare_capacity_mut()[..additional]);
+        unsafe {
+            //NOTE: `squeeze_uninit` initialized the `additional` bytes after
+            //      the end of `out`
+            out.set_len(len + additional);
+        }
     }
 }
 
-// Don't derive(Debug), because the state may be secret
+// Don't derive(Debug), because the state may be secret. The position isn't
 impl fmt::Debug for OutputReader {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        f.debug_struct("OutputReader").finish_non_exhaustive()
+        f.debug_struct("OutputReader")
+            .field("position", &self.1)
+            .finish_non_exhaustive()
     }
 }
 
+#[cfg(feature = "std")]
 impl std::io::Read for OutputReader {
     #[inline]
     fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
         self.squeeze(buf);
         Ok(buf.len())
     }
+
+    //NOTE: the default implementation only fills the first non-empty buffer,
+    //      but there's always more output, so every buffer is filled
+    fn read_vectored(&mut self, bufs: &mut [std::io::IoSliceMut<'_>]) -> std::io::Result<usize> {
+        let mut len = 0;
+        for buf in bufs {
+            self.squeeze(buf);
+            len += buf.len();
+        }
+        Ok(len)
+    }
 }
@@ -0,0 +1,149 @@
+//! Hashing of untrusted input with a bound on its length
+
+use crate::{error::LimitExceededError, Hasher, OutputReader, SecurityLevel};
+use core::fmt;
+
+/// An incremental hash state that refuses to absorb more than a fixed number
+/// of bytes
+///
+/// This is meant for hashing streams of untrusted input, where a malicious
+/// peer could otherwise make the hash state absorb an unbounded amount of
+/// data. Any [`update`](#method.update) that would take the total input past
+/// the limit returns a [`LimitExceededError`] without absorbing any of its
+/// input, so the state is left as it was before the call
+///
+/// The `N` parameter indicates the security strength level in number of bits.
+/// Valid values for it are:
+///
+/// - [`KT128`](crate::KT128)
+/// - [`KT256`](crate::KT256)
+///
+/// Any other value will fail to compile
+///
+/// # Examples
+///
+/// ```
+/// # use marsupial::{LimitedHasher, KT128};
+/// let mut hasher = LimitedHasher::<KT128>::new(4);
+/// hasher.update(b"foo").expect("within the limit");
+/// assert!(hasher.update(b"bar").is_err());
+/// assert_eq!(hasher.finalize(), marsupial::hash::<KT128>(b"foo"));
+/// ```
+pub struct LimitedHasher<N> {
+    hasher: Hasher<N>,
+    len: u64,
+    max_len: u64,
+}
+
+impl<N> LimitedHasher<N>
+where
+    N: SecurityLevel,
+{
+    /// Construct a new [`LimitedHasher`] for the regular hash function, which
+    /// absorbs at most `max_len` bytes
+    pub fn new(max_len: u64) -> Self {
+        Self {
+            hasher: Hasher::new(),
+            len: 0,
+            max_len,
+        }
+    }
+
+    /// Add input bytes to the hash state, unless that would take the total
+    /// input past the limit, in which case none of them are added and a
+    /// [`LimitExceededError`] is returned. You can call this any number of
+    /// times, until the [`LimitedHasher`] is finalized
+    pub fn update(&mut self, input: impl AsRef<[u8]>) -> Result<&mut Self, LimitExceededError> {
+        let input = input.as_ref();
+        match self.len.checked_add(input.len() as u64) {
+            Some(len) if len <= self.max_len => {
+                self.hasher.update(input);
+                self.len = len;
+                Ok(self)
+            }
+            _ => Err(LimitExceededError::new(self.max_len)),
+        }
+    }
+
+    /// The number of bytes absorbed so far
+    #[inline]
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    /// Whether no bytes have been absorbed so far
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The number of bytes that can still be absorbed before reaching the
+    /// limit
+    #[inline]
+    pub fn remaining(&self) -> u64 {
+        self.max_len - self.len
+    }
+
+    /// Finalize the hash state, consuming the [`LimitedHasher`], and return
+    /// the [`struct@Hash`](crate::Hash) of the input. This method is
+    /// equivalent to [`finalize_custom`](#method.finalize_custom) with an
+    /// empty customization string
+    #[must_use]
+    pub fn finalize(self) -> N::Hash {
+        self.hasher.finalize()
+    }
+
+    /// Finalize the hash state, consuming the [`LimitedHasher`], and return
+    /// the [`struct@Hash`](crate::Hash) of the input
+    #[must_use]
+    pub fn finalize_custom(self, customization: &[u8]) -> N::Hash {
+        self.hasher.finalize_custom(customization)
+    }
+
+    /// Finalize the hash state, consuming the [`LimitedHasher`] and returning
+    /// an [`OutputReader`], which can supply any number of output bytes.
+    /// This method is equivalent to
+    /// [`finalize_custom_xof`](#method.finalize_custom_xof) with an empty
+    /// customization string
+    #[must_use]
+    pub fn finalize_xof(self) -> OutputReader {
+        self.hasher.finalize_xof()
+    }
+
+    /// Finalize the hash state, consuming the [`LimitedHasher`] and returning
+    /// an [`OutputReader`], which can supply any number of output bytes
+    #[must_use]
+    pub fn finalize_custom_xof(self, customization: &[u8]) -> OutputReader {
+        self.hasher.finalize_custom_xof(customization)
+    }
+
+    /// Extract the underlying [`Hasher`], dropping the limit
+    pub fn into_inner(self) -> Hasher<N> {
+        self.hasher
+    }
+}
+
+impl<N> Clone for LimitedHasher<N>
+where
+    N: SecurityLevel,
+{
+    fn clone(&self) -> Self {
+        Self {
+            hasher: self.hasher.clone(),
+            len: self.len,
+            max_len: self.max_len,
+        }
+    }
+}
+
+impl<N> fmt::Debug for LimitedHasher<N>
+where
+    N: SecurityLevel,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LimitedHasher")
+            .field("len", &self.len)
+            .field("max_len", &self.max_len)
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,183 @@
+//! Runtime checks of the XKCP/K12 implementation this crate was built with
+//!
+//! The `Optimized64` implementation chooses between SSSE3, AVX2, and AVX512
+//! code at runtime, based on what the CPU reports supporting. If that choice
+//! is ever wrong, the result is either an illegal instruction, which kills
+//! the process before anything can be reported, or code that runs but
+//! produces wrong output. The latter is what these checks catch
+
+use crate::{error::SelfTestError, Hasher, SecurityLevel, KT128, KT256};
+#[cfg(feature = "alloc")]
+use alloc::vec;
+
+/// The length of the pattern hashed by [`update_pattern`] (17⁴ bytes). This
+/// spans enough chunks for the widest parallel permutation to be used
+const PATTERN_LEN: usize = 17 * 17 * 17 * 17;
+
+/// The KT128 hash of an empty input, with an empty customization string
+const EMPTY_KT128: [u8; 32] = [
+    0x1a, 0xc2, 0xd4, 0x50, 0xfc, 0x3b, 0x42, 0x05, 0xd1, 0x9d, 0xa7, 0xbf, 0xca, 0x1b, 0x37, 0x51,
+    0x3c, 0x08, 0x03, 0x57, 0x7a, 0xc7, 0x16, 0x7f, 0x06, 0xfe, 0x2c, 0xe1, 0xf0, 0xef, 0x39, 0xe5,
+];
+
+/// The KT128 hash of the pattern hashed by [`update_pattern`], with an empty
+/// customization string
+const PATTERN_KT128: [u8; 32] = [
+    0x87, 0x01, 0x04, 0x5e, 0x22, 0x20, 0x53, 0x45, 0xff, 0x4d, 0xda, 0x05, 0x55, 0x5c, 0xbb, 0x5c,
+    0x3a, 0xf1, 0xa7, 0x71, 0xc2, 0xb8, 0x9b, 0xae, 0xf3, 0x7d, 0xb4, 0x3d, 0x99, 0x98, 0xb9, 0xfe,
+];
+
+/// The KT256 hash of an empty input, with an empty customization string
+const EMPTY_KT256: [u8; 64] = [
+    0xb2, 0x3d, 0x2e, 0x9c, 0xea, 0x9f, 0x49, 0x04, 0xe0, 0x2b, 0xec, 0x06, 0x81, 0x7f, 0xc1, 0x0c,
+    0xe3, 0x8c, 0xe8, 0xe9, 0x3e, 0xf4, 0xc8, 0x9e, 0x65, 0x37, 0x07, 0x6a, 0xf8, 0x64, 0x64, 0x04,
+    0xe3, 0xe8, 0xb6, 0x81, 0x07, 0xb8, 0x83, 0x3a, 0x5d, 0x30, 0x49, 0x0a, 0xa3, 0x34, 0x82, 0x35,
+    0x3f, 0xd4, 0xad, 0xc7, 0x14, 0x8e, 0xcb, 0x78, 0x28, 0x55, 0x00, 0x3a, 0xae, 0xbd, 0xe4, 0xa9,
+];
+
+/// The KT256 hash of the pattern hashed by [`update_pattern`], with an empty
+/// customization string
+const PATTERN_KT256: [u8; 64] = [
+    0xb0, 0x62, 0x75, 0xd2, 0x84, 0xcd, 0x1c, 0xf2, 0x05, 0xbc, 0xbe, 0x57, 0xdc, 0xcd, 0x3e, 0xc1,
+    0xff, 0x66, 0x86, 0xe3, 0xed, 0x15, 0x77, 0x63, 0x83, 0xe1, 0xf2, 0xfa, 0x3c, 0x6a, 0xc8, 0xf0,
+    0x8b, 0xf8, 0xa1, 0x62, 0x82, 0x9d, 0xb1, 0xa4, 0x4b, 0x2a, 0x43, 0xff, 0x83, 0xdd, 0x89, 0xc3,
+    0xcf, 0x1c, 0xeb, 0x61, 0xed, 0xe6, 0x59, 0x76, 0x6d, 0x5c, 0xcf, 0x81, 0x7a, 0x62, 0xba, 0x8d,
+];
+
+/// Add the bytes 0x00 to 0xFA, repeated until [`PATTERN_LEN`] bytes have
+/// been produced (as used by the KangarooTwelve test vectors), to `hasher`
+///
+/// The pattern is generated at runtime rather than stored, so that it doesn't
+/// take up space in every binary. XKCP/K12 only uses its parallel
+/// permutations for chunks passed to it in the same call, so with `alloc`, it
+/// is generated on the heap and passed all at once. Otherwise, it is passed a
+/// chunk at a time from the stack, which only exercises the serial one
+fn update_pattern<N>(hasher: &mut Hasher<N>)
+where
+    N: SecurityLevel,
+{
+    #[cfg(feature = "alloc")]
+    let mut buffer = vec![0; PATTERN_LEN];
+    #[cfg(not(feature = "alloc"))]
+    let mut buffer = [0; crate::CHUNK_SIZE];
+
+    let mut offset = 0;
+    while offset < PATTERN_LEN {
+        let len = buffer.len().min(PATTERN_LEN - offset);
+        for (i, byte) in buffer[..len].iter_mut().enumerate() {
+            *byte = ((offset + i) % 251) as u8;
+        }
+        hasher.update(&buffer[..len]);
+        offset += len;
+    }
+}
+
+/// Hash the input added by `update` with a [`Hasher`], and compare the result
+/// against `expected`
+fn known_answer<N>(
+    name: &'static str,
+    update: fn(&mut Hasher<N>),
+    expected: &[u8],
+) -> Result<(), SelfTestError>
+where
+    N: SecurityLevel,
+{
+    let mut hasher = Hasher::<N>::new_unchecked();
+    update(&mut hasher);
+    if hasher.finalize_verify(expected) {
+        Ok(())
+    } else {
+        Err(SelfTestError::new(name))
+    }
+}
+
+/// Run a set of known-answer tests against the XKCP/K12 implementation this
+/// crate was built with, returning an error describing the first one that
+/// fails
+///
+/// Both KT128 and KT256 are tested, with an empty input and with a 17⁴-byte
+/// input, which is long enough for XKCP/K12 to use its parallel permutations
+/// when the `alloc` feature is enabled. Each input goes through the full
+/// [`Hasher`] path. This is meant to be run at startup by deployments that
+/// need to verify the compiled implementation before trusting it (i.e. as a
+/// power-on self-test)
+///
+/// # Examples
+///
+/// ```
+/// marsupial::selftest().expect("the KangarooTwelve implementation is broken");
+/// ```
+pub fn selftest() -> Result<(), SelfTestError> {
+    known_answer::<KT128>("KT128 of an empty input", |_| {}, &EMPTY_KT128)?;
+    known_answer::<KT128>(
+        "KT128 of a 17^4-byte pattern",
+        update_pattern,
+        &PATTERN_KT128,
+    )?;
+    known_answer::<KT256>("KT256 of an empty input", |_| {}, &EMPTY_KT256)?;
+    known_answer::<KT256>(
+        "KT256 of a 17^4-byte pattern",
+        update_pattern,
+        &PATTERN_KT256,
+    )
+}
+
+/// Run [`selftest`], and panic if it fails, as
+/// [`check_dispatch_once`] does the first time it is called
+#[cfg(test)]
+pub(crate) fn check_dispatch() {
+    if let Err(error) = selftest() {
+        dispatch_failed(error);
+    }
+}
+
+/// Panic, reporting that [`selftest`] failed with `error`
+#[cfg_attr(not(feature = "self-test"), allow(dead_code))]
+fn dispatch_failed(error: SelfTestError) -> ! {
+    panic!(
+        "SIMD dispatch produced wrong result: {} (using the {} implementation of XKCP/K12)",
+        error,
+        crate::active_implementation()
+    );
+}
+
+/// Run [`selftest`] the first time this is called in a process, and panic if
+/// it fails. Calls made by other threads while it is running wait for its
+/// result, so that no [`Hasher`] is used before the dispatch has been checked
+#[cfg(feature = "self-test")]
+pub(crate) fn check_dispatch_once() {
+    use core::{
+        hint,
+        sync::atomic::{AtomicU8, Ordering},
+    };
+
+    const UNCHECKED: u8 = 0;
+    const RUNNING: u8 = 1;
+    const PASSED: u8 = 2;
+    const FAILED: u8 = 3;
+
+    //NOTE: the check constructs its `Hasher`s through `Hasher::new_unchecked`,
+    //      so it doesn't end up back here
+    static STATE: AtomicU8 = AtomicU8::new(UNCHECKED);
+    match STATE.compare_exchange(UNCHECKED, RUNNING, Ordering::Acquire, Ordering::Acquire) {
+        Ok(_) => {
+            //NOTE: the result is stored before panicking, so that the threads
+            //      waiting on it don't wait forever
+            let result = selftest();
+            STATE.store(
+                if result.is_ok() { PASSED } else { FAILED },
+                Ordering::Release,
+            );
+            if let Err(error) = result {
+                dispatch_failed(error);
+            }
+        }
+        Err(_) => loop {
+            match STATE.load(Ordering::Acquire) {
+                PASSED => break,
+                FAILED => panic!("SIMD dispatch produced wrong result in another thread"),
+                _ => hint::spin_loop(),
+            }
+        },
+    }
+}
@@ -0,0 +1,147 @@
+//! Hashing the items of an asynchronous stream, through [`futures_sink::Sink`]
+
+use crate::{error::SinkCanceledError, Hasher, SecurityLevel};
+use bytes::Bytes;
+use core::{
+    convert::Infallible,
+    fmt,
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+use futures_channel::oneshot;
+use futures_sink::Sink;
+
+/// A [`Sink`] that absorbs every [`Bytes`] sent into it into a [`Hasher`]. When
+/// it is closed, the [`struct@Hash`](crate::Hash) of everything sent is
+/// finalized, and delivered to the [`HashSinkOutput`] it was created with.
+/// It is constructed by [`Hasher::sink`]
+///
+/// Hashing never blocks, so the sink is always ready to accept another
+/// buffer
+///
+/// # Panics
+///
+/// Sending a buffer into a [`HashSink`] after it has been closed panics
+pub struct HashSink<N>
+where
+    N: SecurityLevel,
+{
+    state: Option<(Hasher<N>, oneshot::Sender<N::Hash>)>,
+}
+
+/// A [`Future`] resolving to the [`struct@Hash`](crate::Hash) of everything
+/// sent into a [`HashSink`], once it has been closed. If the [`HashSink`] is
+/// dropped without being closed, this resolves to [`SinkCanceledError`]
+/// instead
+pub struct HashSinkOutput<N>(oneshot::Receiver<N::Hash>)
+where
+    N: SecurityLevel;
+
+impl<N> Hasher<N>
+where
+    N: SecurityLevel,
+{
+    /// Construct a new [`HashSink`] for the regular hash function, along with
+    /// the [`HashSinkOutput`] that the [`struct@Hash`](crate::Hash) is
+    /// delivered to once the sink is closed
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use marsupial::{Hasher, KT128};
+    /// use bytes::Bytes;
+    /// use futures::{executor::block_on, SinkExt};
+    ///
+    /// let (mut sink, output) = Hasher::<KT128>::sink();
+    /// block_on(async {
+    ///     sink.send(Bytes::from_static(b"foo")).await.unwrap();
+    ///     sink.send(Bytes::from_static(b"bar")).await.unwrap();
+    ///     sink.close().await.unwrap();
+    ///     assert_eq!(output.await, Ok(marsupial::hash::<KT128>(b"foobar")));
+    /// });
+    /// ```
+    pub fn sink() -> (HashSink<N>, HashSinkOutput<N>) {
+        let (sender, receiver) = oneshot::channel();
+        (
+            HashSink {
+                state: Some((Self::new(), sender)),
+            },
+            HashSinkOutput(receiver),
+        )
+    }
+}
+
+//NOTE: nothing in a `HashSink` is ever pinned, but `Hasher` only implements
+//      `Unpin` when `N` does
+impl<N> Unpin for HashSink<N> where N: SecurityLevel {}
+
+impl<N> Sink<Bytes> for HashSink<N>
+where
+    N: SecurityLevel,
+{
+    type Error = Infallible;
+
+    #[inline]
+    fn poll_ready(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn start_send(self: Pin<&mut Self>, item: Bytes) -> Result<(), Self::Error> {
+        let (hasher, _) = self
+            .get_mut()
+            .state
+            .as_mut()
+            .expect("the sink has already been closed");
+        hasher.update(item);
+        Ok(())
+    }
+
+    #[inline]
+    fn poll_flush(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        if let Some((hasher, sender)) = self.get_mut().state.take() {
+            //NOTE: the output having been dropped just means that nobody is
+            //      interested in the hash
+            let _ = sender.send(hasher.finalize());
+        }
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<N> fmt::Debug for HashSink<N>
+where
+    N: SecurityLevel,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HashSink")
+            .field("closed", &self.state.is_none())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<N> Future for HashSinkOutput<N>
+where
+    N: SecurityLevel,
+{
+    type Output = Result<N::Hash, SinkCanceledError>;
+
+    #[inline]
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        Pin::new(&mut self.get_mut().0)
+            .poll(cx)
+            .map_err(|_| SinkCanceledError)
+    }
+}
+
+impl<N> fmt::Debug for HashSinkOutput<N>
+where
+    N: SecurityLevel,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HashSinkOutput").finish_non_exhaustive()
+    }
+}
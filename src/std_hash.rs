@@ -0,0 +1,74 @@
+//! Adapters for using KangarooTwelve with the [`std::hash`] traits
+
+use crate::{Hasher, KT128};
+use core::hash::{self, BuildHasher};
+
+/// A [`std::hash::Hasher`] backed by a [`Hasher<KT128>`]
+///
+/// [`write`](hash::Hasher::write) absorbs bytes into the underlying
+/// [`Hasher`], and [`finish`](hash::Hasher::finish) squeezes the first 8
+/// output bytes from a copy of it, interpreting them as a little-endian
+/// `u64`. It is usually constructed by a [`K12BuildHasher`]
+#[derive(Clone, Debug, Default)]
+pub struct K12StdHasher(Hasher<KT128>);
+
+impl K12StdHasher {
+    /// Construct a new [`K12StdHasher`] with no key
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl hash::Hasher for K12StdHasher {
+    #[inline]
+    fn write(&mut self, bytes: &[u8]) {
+        self.0.update(bytes);
+    }
+
+    fn finish(&self) -> u64 {
+        let mut output = [0; 8];
+        self.0.clone().finalize_xof().squeeze(&mut output);
+        u64::from_le_bytes(output)
+    }
+}
+
+/// A [`BuildHasher`] producing [`K12StdHasher`]s, for using KangarooTwelve
+/// as the hashing algorithm of a [`HashMap`](std::collections::HashMap) or
+/// [`HashSet`](std::collections::HashSet)
+///
+/// The [`Default`] implementation produces unkeyed hashers, so every program
+/// hashes the same values to the same `u64`s. Resisting hash flooding
+/// requires the key to be unpredictable, so when the keys of the map come
+/// from untrusted input, construct it with [`with_key`](#method.with_key)
+/// and a secret, randomly generated key instead
+///
+/// # Examples
+///
+/// ```
+/// # use marsupial::K12BuildHasher;
+/// # use std::collections::HashMap;
+/// let mut map = HashMap::with_hasher(K12BuildHasher::with_key([7; 32]));
+/// map.insert("foo", 1);
+/// assert_eq!(map.get("foo"), Some(&1));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct K12BuildHasher(K12StdHasher);
+
+impl K12BuildHasher {
+    /// Construct a new [`K12BuildHasher`] whose hashers are keyed with the
+    /// provided key, which is absorbed before any other input
+    pub fn with_key(key: [u8; 32]) -> Self {
+        let mut hasher = K12StdHasher::new();
+        hasher.0.update(key);
+        Self(hasher)
+    }
+}
+
+impl BuildHasher for K12BuildHasher {
+    type Hasher = K12StdHasher;
+
+    #[inline]
+    fn build_hasher(&self) -> Self::Hasher {
+        self.0.clone()
+    }
+}
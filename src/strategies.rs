@@ -0,0 +1,37 @@
+//! [`proptest`] strategies for generating hash function inputs and
+//! [`struct@Hash`]es, for use in property tests of code built on top of this
+//! crate
+//!
+//! # Examples
+//!
+//! ```
+//! # use marsupial::KT128;
+//! use proptest::prelude::*;
+//!
+//! proptest! {
+//!     fn hashing_is_deterministic((input, _, _) in marsupial::strategies::input()) {
+//!         prop_assert_eq!(marsupial::hash::<KT128>(&input), marsupial::hash::<KT128>(&input));
+//!     }
+//! }
+//! # hashing_is_deterministic();
+//! ```
+
+use crate::Hash;
+use alloc::vec::Vec;
+use proptest::{collection, prelude::*};
+
+/// A strategy generating [`struct@Hash`]es filled with arbitrary bytes
+pub fn hash<const N: usize>() -> impl Strategy<Value = Hash<N>> {
+    proptest::array::uniform(any::<u8>()).prop_map(Hash::from)
+}
+
+/// A strategy generating `(input, customization, output_len)` triples, where
+/// the input and customization string are each up to 10,000 bytes long and
+/// the output length is below 1,000 bytes
+pub fn input() -> impl Strategy<Value = (Vec<u8>, Vec<u8>, usize)> {
+    (
+        collection::vec(any::<u8>(), 0..10_000),
+        collection::vec(any::<u8>(), 0..10_000),
+        0usize..1_000usize,
+    )
+}
@@ -0,0 +1,97 @@
+//! Unambiguous hashing of records made up of named fields
+
+use crate::{Hasher, SecurityLevel};
+use core::fmt;
+
+/// An incremental hash state over a record of named fields
+///
+/// Hashing the concatenation of a record's fields makes it ambiguous where
+/// one field ends and the next begins, so records that only differ in how
+/// their bytes are split between fields can collide. A [`StructHasher`]
+/// avoids that by framing every field name and value
+///
+/// # Encoding
+///
+/// Each field is absorbed as its name followed by its value, each encoded as
+/// by [`Hasher::update_frame`]: its length (in bytes) as a little-endian
+/// `u64`, followed by its bytes. No other bytes are added, so the digest of a
+/// record is the digest of its encoding under a plain [`Hasher`]
+///
+/// Fields are absorbed in the order they're added, and the same name may be
+/// added more than once. The encoding doesn't sort or deduplicate them, so
+/// the caller decides what the canonical order of a record's fields is
+///
+/// The `N` parameter indicates the security strength level in number of bits.
+/// Valid values for it are:
+///
+/// - [`KT128`](crate::KT128)
+/// - [`KT256`](crate::KT256)
+///
+/// Any other value will fail to compile
+///
+/// # Examples
+///
+/// ```
+/// # use marsupial::{StructHasher, KT128};
+/// let mut hasher = StructHasher::<KT128>::new();
+/// hasher.field(b"name", b"marsupial");
+/// hasher.field(b"kind", b"crate");
+///
+/// let mut other = StructHasher::<KT128>::new();
+/// other.field(b"name", b"marsupialkind");
+/// other.field(b"", b"crate");
+///
+/// assert_ne!(hasher.finish(), other.finish());
+/// ```
+pub struct StructHasher<N>(Hasher<N>);
+
+impl<N> StructHasher<N>
+where
+    N: SecurityLevel,
+{
+    /// Construct a new [`StructHasher`] for the regular hash function
+    pub fn new() -> Self {
+        Self(Hasher::new())
+    }
+
+    /// Add a field to the record. You can call this any number of times,
+    /// until the [`StructHasher`] is finished
+    pub fn field(&mut self, name: &[u8], value: &[u8]) -> &mut Self {
+        self.0.update_frame(name).update_frame(value);
+        self
+    }
+
+    /// Finalize the hash state, consuming the [`StructHasher`], and return
+    /// the [`struct@Hash`](crate::Hash) of the record
+    #[must_use]
+    pub fn finish(self) -> N::Hash {
+        self.0.finalize()
+    }
+}
+
+impl<N> Clone for StructHasher<N>
+where
+    N: SecurityLevel,
+{
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<N> Default for StructHasher<N>
+where
+    N: SecurityLevel,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<N> fmt::Debug for StructHasher<N>
+where
+    N: SecurityLevel,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StructHasher").finish_non_exhaustive()
+    }
+}
@@ -1,15 +1,85 @@
-use crate::{hash, Hasher, KT128, KT256};
+use crate::{
+    hash, hash_iter, hash_slices, FramedHasher, Hash, Hasher, TryFromSliceError, CHUNK_SIZE, KT128,
+    KT256,
+};
 use digest::{ExtendableOutput, Update, XofReader};
 use proptest::{collection, prelude::*};
 use tiny_keccak::{IntoXof, Xof};
 
 fn fill_pattern(buf: &mut [u8]) {
     // repeating the pattern 0x00, 0x01, 0x02, ..., 0xFA as many times as necessary
-    for i in 0..buf.len() {
-        buf[i] = (i % 251) as u8;
+    for (i, byte) in buf.iter_mut().enumerate() {
+        *byte = (i % 251) as u8;
     }
 }
 
+// a deliberately simple implementation of TurboSHAKE and KangarooTwelve, as
+// specified in https://datatracker.ietf.org/doc/draft-irtf-cfrg-kangarootwelve/,
+// built upon tiny-keccak's Keccak-p[1600, 12] permutation. neither the k12
+// crate nor the tiny-keccak crate implement KT256, so this is what it's
+// cross-checked against
+
+fn reference_turboshake(capacity: usize, input: &[u8], domain: u8, output: &mut [u8]) {
+    let rate = 200 - capacity;
+    let mut padded = input.to_vec();
+    padded.push(domain);
+    padded.resize(padded.len().next_multiple_of(rate), 0);
+    *padded.last_mut().unwrap() ^= 0x80;
+
+    let mut state = [0u64; 25];
+    for block in padded.chunks(rate) {
+        for (i, byte) in block.iter().enumerate() {
+            state[i / 8] ^= (*byte as u64) << (8 * (i % 8));
+        }
+        tiny_keccak::keccakp(&mut state);
+    }
+    for block in output.chunks_mut(rate) {
+        for (i, byte) in block.iter_mut().enumerate() {
+            *byte = (state[i / 8] >> (8 * (i % 8))) as u8;
+        }
+        tiny_keccak::keccakp(&mut state);
+    }
+}
+
+fn reference_length_encode(x: usize) -> Vec<u8> {
+    let mut encoded: Vec<u8> = x
+        .to_be_bytes()
+        .into_iter()
+        .skip_while(|b| *b == 0)
+        .collect();
+    encoded.push(encoded.len() as u8);
+    encoded
+}
+
+// `capacity` is in bytes, and is 32 for KT128 and 64 for KT256
+fn reference_kangarootwelve(
+    capacity: usize,
+    input: &[u8],
+    customization: &[u8],
+    output: &mut [u8],
+) {
+    let mut s = input.to_vec();
+    s.extend_from_slice(customization);
+    s.extend(reference_length_encode(customization.len()));
+
+    if s.len() <= CHUNK_SIZE {
+        return reference_turboshake(capacity, &s, 0x07, output);
+    }
+
+    let mut node = s[..CHUNK_SIZE].to_vec();
+    node.extend_from_slice(&[0x03, 0, 0, 0, 0, 0, 0, 0]);
+    let leaves = s[CHUNK_SIZE..].chunks(CHUNK_SIZE);
+    let leaf_count = leaves.len();
+    for leaf in leaves {
+        let mut chaining_value = vec![0; capacity];
+        reference_turboshake(capacity, leaf, 0x0b, &mut chaining_value);
+        node.extend(chaining_value);
+    }
+    node.extend(reference_length_encode(leaf_count));
+    node.extend_from_slice(&[0xff, 0xff]);
+    reference_turboshake(capacity, &node, 0x06, output);
+}
+
 fn kt256_hex(input: &[u8], customization: &[u8], num_output_bytes: usize) -> String {
     let mut hasher = Hasher::<KT256>::new();
     hasher.update(input);
@@ -35,6 +105,11 @@ fn kt256_hex(input: &[u8], customization: &[u8], num_output_bytes: usize) -> Str
         assert_eq!(&hash3.as_bytes()[..compare_len], &output[..compare_len]);
     }
 
+    // check that the reference implementation gives the same answer
+    let mut reference_output = vec![0; num_output_bytes];
+    reference_kangarootwelve(64, input, customization, &mut reference_output);
+    assert_eq!(output, reference_output);
+
     hex::encode(output)
 }
 
@@ -71,6 +146,11 @@ fn kt128_hex(input: &[u8], customization: &[u8], num_output_bytes: usize) -> Str
     k12_reader.read(&mut k12_output);
     assert_eq!(output, k12_output);
 
+    // check that the reference implementation gives the same answer
+    let mut reference_output = vec![0; num_output_bytes];
+    reference_kangarootwelve(32, input, customization, &mut reference_output);
+    assert_eq!(output, reference_output);
+
     // finally, check that the tiny-keccak crate gives the same answer
     let mut tk_state = tiny_keccak::KangarooTwelve::new(customization);
     <tiny_keccak::KangarooTwelve<&[u8]> as tiny_keccak::Hasher>::update(&mut tk_state, input);
@@ -398,3 +478,1742 @@ fn test_vector_32() {
     fill_pattern(&mut customization);
     assert_eq!(expected, kt256_hex(&input, &customization, 64));
 }
+
+#[test]
+fn test_kt256_custom_xof_long_output() {
+    // there's no published vector squeezing a long KT256 output with a
+    // customization string, so this checks one (as long as vector 17's) against
+    // the reference implementation, both in a single squeeze and in pieces
+    for (input_len, customization_len) in [(0, 41), (17, 41 * 41), (17 * 17 * 17, 41)] {
+        let mut input = vec![0; input_len];
+        fill_pattern(&mut input);
+        let mut customization = vec![0; customization_len];
+        fill_pattern(&mut customization);
+
+        let out = hex::decode(kt256_hex(&input, &customization, 10064)).unwrap();
+
+        let mut hasher = Hasher::<KT256>::new();
+        hasher.update(&input);
+        let mut output_reader = hasher.finalize_custom_xof(&customization);
+        let mut pieces = vec![0; 10064];
+        for piece in pieces.chunks_mut(1000) {
+            output_reader.squeeze(piece);
+        }
+        assert_eq!(&out[out.len() - 64..], &pieces[pieces.len() - 64..]);
+        assert_eq!(out, pieces);
+    }
+}
+
+#[test]
+fn test_hash_slices() {
+    assert_eq!(
+        hash_slices::<KT128>(&[b"foo", b"bar"]),
+        hash::<KT128>(b"foobar")
+    );
+    assert_eq!(
+        hash_slices::<KT256>(&[b"foo", b"", b"bar"]),
+        hash::<KT256>(b"foobar")
+    );
+}
+
+#[test]
+fn test_hash_try_from_slice() {
+    let hash = hash::<KT128>(b"foobarbaz");
+    assert_eq!(Ok(hash), Hash::<32>::try_from(&hash.as_bytes()[..]));
+
+    let error = Hash::<32>::try_from(&hash.as_bytes()[..31]).unwrap_err();
+    assert_eq!(error, TryFromSliceError::new(32, 31));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_hash_try_from_vec() {
+    let hash = hash::<KT256>(b"foobarbaz");
+    assert_eq!(Ok(hash), Hash::<64>::try_from(hash.as_bytes().to_vec()));
+
+    let error = Hash::<64>::try_from(vec![0; 65]).unwrap_err();
+    assert_eq!(error.expected(), 64);
+    assert_eq!(error.actual(), 65);
+}
+
+#[cfg(feature = "arbitrary")]
+#[test]
+fn test_hash_arbitrary() {
+    use arbitrary::{Arbitrary, Unstructured};
+
+    let mut bytes = [0; 40];
+    fill_pattern(&mut bytes);
+    let mut unstructured = Unstructured::new(&bytes);
+    let hash = Hash::<32>::arbitrary(&mut unstructured).unwrap();
+    assert_eq!(hash.as_bytes()[..], bytes[..32]);
+}
+
+#[test]
+fn test_finalize_verify() {
+    let expected = hash::<KT128>(b"foobarbaz");
+    let mut hasher = Hasher::<KT128>::new();
+    hasher.update(b"foobarbaz");
+    assert!(hasher.clone().finalize_verify(expected.as_bytes()));
+    assert!(!hasher.clone().finalize_verify(&expected.as_bytes()[..31]));
+    assert!(!hasher
+        .clone()
+        .finalize_custom_verify(b"foo", expected.as_bytes()));
+
+    let mut different = *expected.as_bytes();
+    different[0] ^= 1;
+    assert!(!hasher.finalize_verify(&different));
+
+    let mut hasher = Hasher::<KT256>::new();
+    hasher.update(b"foobarbaz");
+    let expected = hasher.clone().finalize_custom(b"foo");
+    assert!(hasher
+        .clone()
+        .finalize_custom_verify(b"foo", expected.as_bytes()));
+    assert!(!hasher.finalize_verify(expected.as_bytes()));
+}
+
+#[test]
+fn test_squeeze_uninit() {
+    let mut hasher = Hasher::<KT128>::new();
+    hasher.update(b"foobarbaz");
+    let mut reader = hasher.finalize_xof();
+    let mut reader2 = reader.clone();
+
+    let mut expected = [0; 1000];
+    reader.squeeze(&mut expected);
+
+    let mut output = [std::mem::MaybeUninit::uninit(); 1000];
+    assert_eq!(&expected[..500], reader2.squeeze_uninit(&mut output[..500]));
+    assert_eq!(&expected[500..], reader2.squeeze_uninit(&mut output[500..]));
+}
+
+#[test]
+fn test_update_as_ref() {
+    let bar: Vec<u8> = vec![b'b', b'a', b'r'];
+    let mut hasher = Hasher::<KT128>::new();
+    hasher.update(String::from("foo"));
+    hasher.update(bar);
+    hasher.update("baz");
+    assert_eq!(hasher.finalize(), hash::<KT128>(b"foobarbaz"));
+}
+
+#[test]
+fn test_hash_std_hash_matches_eq() {
+    use std::collections::HashSet;
+
+    let digest = hash::<KT128>(b"foobarbaz");
+    let mut set = HashSet::new();
+    set.insert(digest);
+    set.insert(Hash::from(*digest.as_bytes()));
+    set.insert(Hash::try_from(&digest.as_bytes()[..]).unwrap());
+    assert_eq!(set.len(), 1);
+
+    set.insert(hash::<KT128>(b"foobar"));
+    assert_eq!(set.len(), 2);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_output_reader_raw_state() {
+    use crate::OutputReader;
+
+    let mut hasher = Hasher::<KT256>::new();
+    hasher.update(b"foobarbaz");
+    let mut reader = hasher.finalize_custom_xof(b"custom");
+    let mut expected = [0; 64];
+    reader.clone().squeeze(&mut expected);
+
+    let mut output = [0; 64];
+    reader.squeeze(&mut output[..32]);
+    let state = reader.to_raw_state();
+    assert_eq!(state.len(), OutputReader::RAW_STATE_LEN);
+
+    let mut reader = unsafe { OutputReader::from_raw_state(&state) };
+    assert_eq!(reader.position(), 32);
+    reader.squeeze(&mut output[32..]);
+    assert_eq!(expected, output);
+}
+
+#[test]
+fn test_active_implementation() {
+    assert!(!crate::active_implementation().is_empty());
+}
+
+#[test]
+fn test_hash_ct_eq_and_fast_eq() {
+    let a = hash::<KT128>(b"foobarbaz");
+    let b = Hash::from(*a.as_bytes());
+    let c = hash::<KT128>(b"foobar");
+    assert!(a.ct_eq(&b));
+    assert!(a.fast_eq(&b));
+    assert!(!a.ct_eq(&c));
+    assert!(!a.fast_eq(&c));
+}
+
+#[cfg(feature = "bytes")]
+#[test]
+fn test_update_buf() {
+    use bytes::{Buf, Bytes};
+
+    let mut buf = Bytes::from_static(b"foo").chain(Bytes::from_static(b"barbaz"));
+    let mut hasher = Hasher::<KT128>::new();
+    hasher.update_buf(&mut buf);
+    assert!(!buf.has_remaining());
+    assert_eq!(hasher.finalize(), hash::<KT128>(b"foobarbaz"));
+}
+
+#[test]
+fn test_hasher_output_len_and_security_bits() {
+    assert_eq!(Hasher::<KT128>::output_len(), 32);
+    assert_eq!(Hasher::<KT128>::security_bits(), 128);
+    assert_eq!(Hasher::<KT256>::output_len(), 64);
+    assert_eq!(Hasher::<KT256>::security_bits(), 256);
+}
+
+#[test]
+fn test_output_reader_position() {
+    let mut reader = Hasher::<KT128>::new().finalize_xof();
+    assert_eq!(reader.position(), 0);
+    assert_eq!(format!("{reader:?}"), "OutputReader { position: 0, .. }");
+
+    reader.squeeze(&mut [0; 100]);
+    reader.squeeze_uninit(&mut [std::mem::MaybeUninit::uninit(); 50]);
+    assert_eq!(reader.position(), 150);
+    assert_eq!(format!("{reader:?}"), "OutputReader { position: 150, .. }");
+}
+
+#[cfg(feature = "proptest")]
+proptest! {
+    #[test]
+    fn validate_strategies(
+        (data, customization, size) in crate::strategies::input(),
+        digest in crate::strategies::hash::<32>(),
+    ) {
+        prop_assert_eq!(hash::<KT128>(&data), hash::<KT128>(&data));
+        prop_assert_eq!(size * 2, kt128_hex(&data, &customization, size).len());
+        prop_assert_eq!(Hash::from(*digest.as_bytes()), digest);
+    }
+}
+
+#[test]
+fn test_framed_hasher() {
+    let mut plain1 = Hasher::<KT128>::new();
+    plain1.update(b"ab");
+    plain1.update(b"c");
+    let mut plain2 = Hasher::<KT128>::new();
+    plain2.update(b"a");
+    plain2.update(b"bc");
+    assert_eq!(plain1.finalize(), plain2.finalize());
+
+    let mut framed1 = FramedHasher::<KT128>::new();
+    framed1.push(b"ab");
+    framed1.push(b"c");
+    let mut framed2 = FramedHasher::<KT128>::new();
+    framed2.push(b"a");
+    framed2.push(b"bc");
+    let digest = framed1.finalize();
+    assert_ne!(digest, framed2.finalize());
+
+    let mut framing = Vec::new();
+    framing.extend_from_slice(&2u64.to_le_bytes());
+    framing.extend_from_slice(b"ab");
+    framing.extend_from_slice(&1u64.to_le_bytes());
+    framing.extend_from_slice(b"c");
+    assert_eq!(digest, hash::<KT128>(&framing));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_hash_hex() {
+    use crate::hash_hex;
+
+    let expected = "1ac2d450fc3b4205d19da7bfca1b37513c0803577ac7167f06fe2ce1f0ef39e5";
+    assert_eq!(expected, hash_hex::<KT128>(b""));
+    assert_eq!(expected, hash::<KT128>(b"").to_hex());
+    assert_eq!(kt256_hex(b"foo", &[], 64), hash_hex::<KT256>(b"foo"));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_copy_and_hash() {
+    use crate::copy_and_hash;
+    use std::io::Cursor;
+
+    let mut input = vec![0; 100_000];
+    fill_pattern(&mut input);
+    let mut reader = Cursor::new(&input);
+    let mut writer = Vec::new();
+    let (len, digest) = copy_and_hash::<KT256, _, _>(&mut reader, &mut writer).unwrap();
+    assert_eq!(len, input.len() as u64);
+    assert_eq!(writer, input);
+    assert_eq!(digest, hash::<KT256>(&input));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_hash_hex_formats() {
+    let digest = Hash::from([0xaa, 0xbb, 0xcc, 0x0f]);
+    assert_eq!("aabbcc0f", digest.to_hex());
+    assert_eq!("AABBCC0F", digest.to_hex_upper());
+    assert_eq!("aa:bb:cc:0f", digest.to_hex_separated(':', 2));
+    assert_eq!("aabb cc0f", digest.to_hex_separated(' ', 4));
+    assert_eq!("aabbcc0f", digest.to_hex_separated('-', 8));
+    assert_eq!("a-a-b-b-c-c-0-f", digest.to_hex_separated('-', 1));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_finalize_custom_slices() {
+    let mut hasher = Hasher::<KT128>::new();
+    hasher.update(b"baz");
+    let expected = hasher.clone().finalize_custom(b"foobar");
+    assert_eq!(
+        expected,
+        hasher.clone().finalize_custom_slices(&[b"foo", b"bar"])
+    );
+    assert_eq!(
+        expected,
+        hasher.finalize_custom_slices(&[b"", b"foobar", b""])
+    );
+}
+
+#[test]
+fn test_hasher_across_threads() {
+    let mut hasher = Hasher::<KT128>::new();
+    hasher.update(b"foo");
+    let reader = std::thread::spawn(move || {
+        hasher.update(b"bar");
+        hasher.finalize_xof()
+    })
+    .join()
+    .unwrap();
+    let mut output = [0; 32];
+    std::thread::scope(|s| {
+        s.spawn(|| reader.clone().squeeze(&mut output));
+    });
+    assert_eq!(output, *hash::<KT128>(b"foobar").as_bytes());
+}
+
+#[test]
+fn test_update_integers() {
+    let mut integers = Hasher::<KT128>::new();
+    integers.update_u64_le(0x0102030405060708);
+    integers.update_u64_be(0x0102030405060708);
+    integers.update_u32_le(0x01020304);
+    integers.update_u32_be(0x01020304);
+    integers.update_u16_le(0x0102);
+    integers.update_u16_be(0x0102);
+
+    let mut bytes = Hasher::<KT128>::new();
+    bytes.update(0x0102030405060708u64.to_le_bytes());
+    bytes.update([1, 2, 3, 4, 5, 6, 7, 8]);
+    bytes.update(0x01020304u32.to_le_bytes());
+    bytes.update([1, 2, 3, 4]);
+    bytes.update(0x0102u16.to_le_bytes());
+    bytes.update([1, 2]);
+
+    assert_eq!(integers.finalize(), bytes.finalize());
+}
+
+//NOTE: a finalized `Hasher` can't be obtained through the public api, so this
+//      reaches into the `OutputReader` for its instance. the assertion is not
+//      a `debug_assert`, so this holds in release builds too. the same goes
+//      for the ones below, which reach the other invalid transitions
+#[test]
+#[should_panic(expected = "this instance has already been finalized")]
+fn test_update_after_finalize_panics() {
+    let reader = Hasher::<KT128>::new().finalize_xof();
+    let mut hasher = Hasher::<KT128>(reader.0, std::marker::PhantomData);
+    hasher.update(b"foo");
+}
+
+#[test]
+#[should_panic(expected = "this instance has already been finalized")]
+fn test_finalize_twice_panics() {
+    let reader = Hasher::<KT256>::new().finalize_xof();
+    let hasher = Hasher::<KT256>(reader.0, std::marker::PhantomData);
+    let _ = hasher.finalize();
+}
+
+#[test]
+#[should_panic(expected = "this instance has not yet been finalized")]
+fn test_squeeze_before_finalize_panics() {
+    let hasher = Hasher::<KT128>::new();
+    let mut reader = crate::OutputReader(hasher.0, 0);
+    reader.squeeze(&mut [0; 32]);
+}
+
+#[test]
+#[should_panic(expected = "this instance has not yet been finalized")]
+fn test_squeeze_uninit_before_finalize_panics() {
+    let hasher = Hasher::<KT128>::new();
+    let mut reader = crate::OutputReader(hasher.0, 0);
+    reader.squeeze_uninit(&mut [std::mem::MaybeUninit::uninit(); 32]);
+}
+
+#[cfg(feature = "zerocopy")]
+#[test]
+fn test_hash_typed() {
+    use crate::hash_typed;
+    use zerocopy::{byteorder::little_endian::U32, AsBytes};
+
+    #[derive(AsBytes)]
+    #[repr(C)]
+    struct Header {
+        magic: [u8; 4],
+        length: U32,
+    }
+
+    let header = Header {
+        magic: *b"mars",
+        length: U32::new(0x01020304),
+    };
+    assert_eq!(
+        hash_typed::<KT128, _>(&header),
+        hash::<KT128>(b"mars\x04\x03\x02\x01")
+    );
+}
+
+#[test]
+fn test_sponge_parameters() {
+    assert_eq!(Hasher::<KT128>::CAPACITY_BITS, 256);
+    assert_eq!(Hasher::<KT128>::RATE_BITS, 1344);
+    assert_eq!(Hasher::<KT128>::RATE, 168);
+    assert_eq!(Hasher::<KT256>::CAPACITY_BITS, 512);
+    assert_eq!(Hasher::<KT256>::RATE_BITS, 1088);
+    assert_eq!(Hasher::<KT256>::RATE, 136);
+    assert_eq!(Hasher::<KT128>::ROUNDS, 12);
+    assert_eq!(Hasher::<KT256>::ROUNDS, 12);
+}
+
+#[test]
+fn test_hash_iter() {
+    for len in [0, 1000, 16 * 1024, 40_000] {
+        let input = (0u8..=255).cycle().take(len);
+        let expected = hash::<KT128>(&input.clone().collect::<Vec<_>>());
+        assert_eq!(expected, hash_iter::<KT128, _>(input));
+    }
+}
+
+#[test]
+fn test_digest_and_xof() {
+    let mut hasher = Hasher::<KT256>::new();
+    hasher.update(b"foo");
+    let mut expected = [0; 200];
+    hasher
+        .clone()
+        .finalize_custom_xof(b"bar")
+        .squeeze(&mut expected);
+
+    let (digest, mut reader) = hasher.clone().digest_and_custom_xof(b"bar");
+    assert_eq!(reader.position(), 64);
+    let mut rest = [0; 136];
+    reader.squeeze(&mut rest);
+    assert_eq!(digest.as_bytes()[..], expected[..64]);
+    assert_eq!(rest[..], expected[64..]);
+
+    let (digest, _) = hasher.clone().digest_and_xof();
+    assert_eq!(digest, hasher.finalize());
+}
+
+#[test]
+fn test_k12_build_hasher() {
+    use crate::{K12BuildHasher, K12StdHasher};
+    use std::{
+        collections::HashMap,
+        hash::{BuildHasher, Hasher as _},
+    };
+
+    let mut map = HashMap::with_hasher(K12BuildHasher::with_key([1; 32]));
+    for i in 0..1000u32 {
+        map.insert(i, i.to_string());
+    }
+    for i in 0..1000u32 {
+        assert_eq!(map.get(&i), Some(&i.to_string()));
+    }
+    assert_eq!(map.get(&1000), None);
+
+    let mut std_hasher = K12StdHasher::new();
+    std_hasher.write(b"foo");
+    let mut expected = [0; 8];
+    expected.copy_from_slice(&hash::<KT128>(b"foo").as_bytes()[..8]);
+    assert_eq!(std_hasher.finish(), u64::from_le_bytes(expected));
+    assert_eq!(
+        K12BuildHasher::default().hash_one("foo"),
+        K12BuildHasher::default().hash_one("foo")
+    );
+    assert_ne!(
+        K12BuildHasher::default().hash_one("foo"),
+        K12BuildHasher::with_key([1; 32]).hash_one("foo")
+    );
+}
+
+#[test]
+fn test_squeeze_empty() {
+    let mut reader = Hasher::<KT128>::new().finalize_xof();
+    reader.squeeze(&mut []);
+    assert_eq!(reader.position(), 0);
+    assert!(reader.squeeze_uninit(&mut []).is_empty());
+    assert_eq!(reader.position(), 0);
+
+    let mut output = [0; 32];
+    reader.squeeze(&mut output);
+    assert_eq!(output, *hash::<KT128>(b"").as_bytes());
+}
+
+#[test]
+fn test_xkcp_version() {
+    assert!(!crate::XKCP_VERSION.is_empty());
+}
+
+#[test]
+fn test_discard() {
+    let hash_xof = |input: &[u8]| {
+        let mut hasher = Hasher::<KT128>::new();
+        hasher.update(input);
+        hasher.finalize_xof()
+    };
+    let mut expected = [0; 5000];
+    hash_xof(b"foo").squeeze(&mut expected);
+
+    for skip in [0, 32, 1024, 3000] {
+        let mut reader = hash_xof(b"foo");
+        reader.discard(skip);
+        assert_eq!(reader.position(), skip);
+        let mut output = [0; 32];
+        reader.squeeze(&mut output);
+        let skip = skip as usize;
+        assert_eq!(output[..], expected[skip..skip + 32]);
+    }
+}
+
+#[test]
+fn test_conditional_select() {
+    let a = hash::<KT128>(b"foo");
+    let b = hash::<KT128>(b"bar");
+    assert_eq!(Hash::conditional_select(&a, &b, true), a);
+    assert_eq!(Hash::conditional_select(&a, &b, false), b);
+    assert_eq!(Hash::conditional_select(&a, &a, false), a);
+}
+
+#[test]
+fn test_tree_reconstruction() {
+    // the first chunk, two full leaves, and a partial one
+    let mut input = vec![0; 3 * CHUNK_SIZE + 100];
+    fill_pattern(&mut input);
+
+    // the empty customization string is encoded as a single zero byte, which
+    // belongs to the last leaf
+    let mut s = input.clone();
+    s.push(0);
+
+    let mut node = s[..CHUNK_SIZE].to_vec();
+    node.extend_from_slice(&[0x03, 0, 0, 0, 0, 0, 0, 0]);
+    for leaf in s[CHUNK_SIZE..].chunks(CHUNK_SIZE) {
+        let mut chaining_value = [0; 32];
+        reference_turboshake(32, leaf, 0x0b, &mut chaining_value);
+        node.extend_from_slice(&chaining_value);
+    }
+    node.extend_from_slice(&[3, 1, 0xff, 0xff]);
+
+    let mut root = [0; 32];
+    reference_turboshake(32, &node, 0x06, &mut root);
+    assert_eq!(hash::<KT128>(&input), root);
+}
+
+#[cfg(feature = "unstable-internals")]
+#[test]
+fn test_hasher_state() {
+    use crate::internals::HasherState;
+
+    let mut input = vec![0; 20_000];
+    fill_pattern(&mut input);
+    let mut hasher = Hasher::<KT256>::new();
+    hasher.update(&input);
+
+    let state = HasherState::from(&hasher);
+    assert_eq!(state.phase, 1);
+    assert_eq!(state.security_level, 256);
+
+    let restored = unsafe { state.into_hasher::<KT256>() };
+    assert_eq!(restored.finalize(), hasher.finalize());
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_hash_multi_len() {
+    use crate::hash_multi_len;
+
+    let outputs = hash_multi_len::<KT128>(b"foo", &[64, 16, 0, 32]);
+    assert_eq!(outputs.len(), 4);
+    assert_eq!(outputs[0].len(), 64);
+    assert_eq!(outputs[1][..], outputs[0][..16]);
+    assert!(outputs[2].is_empty());
+    assert_eq!(outputs[3][..], hash::<KT128>(b"foo").as_bytes()[..]);
+    assert!(hash_multi_len::<KT128>(b"foo", &[]).is_empty());
+}
+
+#[test]
+fn test_error_display() {
+    let error = Hash::<32>::try_from(&[0; 16][..]).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "expected 32 bytes to convert into a hash, but got 16"
+    );
+    let boxed: Box<dyn std::error::Error> = Box::new(error);
+    assert!(boxed.source().is_none());
+}
+
+#[test]
+fn test_update_str() {
+    let mut hasher = Hasher::<KT128>::new();
+    hasher.update_str("foo");
+    hasher.update_str("ü");
+    assert_eq!(hasher.finalize(), hash::<KT128>("fooü".as_bytes()));
+}
+
+//NOTE: these are meant to be run on 32-bit targets as well (e.g. with
+//      `cross test --target i686-unknown-linux-gnu`), where `Inplace32BI` is
+//      selected and `usize` is the width of xkcp/k12's counters
+
+#[test]
+fn test_large_chunked_input() {
+    let mut input = vec![0; 4 * 1024 * 1024 + 12345];
+    fill_pattern(&mut input);
+
+    let mut hasher = Hasher::<KT128>::new();
+    for chunk in input.chunks(CHUNK_SIZE * 3 + 7) {
+        hasher.update(chunk);
+    }
+
+    let mut expected = [0; 32];
+    reference_kangarootwelve(32, &input, &[], &mut expected);
+    assert_eq!(hasher.finalize(), expected);
+}
+
+#[test]
+#[should_panic(expected = "the input is too long for the chunk counter")]
+fn test_chunk_counter_overflow_panics() {
+    let mut hasher = Hasher::<KT128>::new();
+    hasher.0.blockNumber = usize::MAX - 1;
+    hasher.update([0; CHUNK_SIZE]);
+}
+
+#[cfg(feature = "digest")]
+#[test]
+fn test_generic_array() {
+    use crate::hash_generic_array;
+    use digest::{generic_array::GenericArray, typenum::U32};
+
+    let digest = hash::<KT128>(b"foo");
+    let array: GenericArray<u8, U32> = digest.into();
+    assert_eq!(array[..], digest.as_bytes()[..]);
+    assert_eq!(Hash::from(array), digest);
+    assert_eq!(hash_generic_array::<KT128>(b"foo"), array);
+    assert_eq!(
+        hash_generic_array::<KT256>(b"foo")[..],
+        hash::<KT256>(b"foo").as_bytes()[..]
+    );
+}
+
+#[test]
+fn test_with_capacity_hint() {
+    for hint in [0, 100, usize::MAX] {
+        let mut hasher = Hasher::<KT256>::with_capacity_hint(hint);
+        hasher.update(b"foo");
+        assert_eq!(hasher.finalize(), hash::<KT256>(b"foo"));
+    }
+}
+
+#[test]
+fn test_streams_equal() {
+    use crate::OutputReader;
+
+    let mut input = vec![0; 10_000];
+    fill_pattern(&mut input);
+    let mut hasher = Hasher::<KT128>::new();
+    hasher.update(&input);
+
+    let mut a = hasher.clone().finalize_xof();
+    let mut b = hasher.clone().finalize_xof();
+    assert!(OutputReader::streams_equal(&mut a, &mut b, 100_000));
+    assert_eq!(a.position(), 100_000);
+    assert!(OutputReader::streams_equal(&mut a, &mut b, 0));
+
+    let mut c = hasher.finalize_custom_xof(b"foo");
+    let mut d = Hasher::<KT128>::new().finalize_xof();
+    assert!(!OutputReader::streams_equal(&mut c, &mut d, 100_000));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_content_id() {
+    use crate::{
+        cas::{ContentId, BASE32_LEN},
+        ContentIdParseError,
+    };
+
+    let id = ContentId::from_content(b"foobarbaz");
+    assert_eq!(*id.hash(), hash::<KT128>(b"foobarbaz"));
+    assert_eq!(
+        id.to_string(),
+        hex::encode(hash::<KT128>(b"foobarbaz").as_bytes())
+    );
+
+    let encoded = id.to_base32();
+    assert_eq!(encoded.len(), BASE32_LEN);
+    assert!(encoded
+        .chars()
+        .all(|c| c.is_ascii_lowercase() || ('2'..='7').contains(&c)));
+    assert_eq!(encoded.parse::<ContentId>(), Ok(id));
+    assert_eq!(ContentId::from_base32(&encoded.to_uppercase()), Ok(id));
+
+    // all zeroes, and all ones (with the padding bits cleared)
+    let zero = ContentId::from(Hash::from([0; 32]));
+    assert_eq!(zero.to_base32(), "a".repeat(BASE32_LEN));
+    let ones = ContentId::from(Hash::from([0xff; 32]));
+    assert_eq!(ones.to_base32(), "7".repeat(BASE32_LEN - 1) + "q");
+    assert_eq!(ContentId::from_base32(&ones.to_base32()), Ok(ones));
+
+    assert_eq!(
+        ContentId::from_base32("abc"),
+        Err(ContentIdParseError::InvalidLength(3))
+    );
+    assert_eq!(
+        ContentId::from_base32(&"1".repeat(BASE32_LEN)),
+        Err(ContentIdParseError::InvalidCharacter('1'))
+    );
+    assert_eq!(
+        ContentId::from_base32(&"7".repeat(BASE32_LEN)),
+        Err(ContentIdParseError::NonCanonical)
+    );
+}
+
+#[test]
+fn test_update_chaining() {
+    let mut sequential = Hasher::<KT128>::new();
+    sequential.update(b"foo");
+    sequential.update_str("bar");
+    sequential.update_u32_le(7);
+
+    let mut chained = Hasher::<KT128>::new();
+    chained.update(b"foo").update_str("bar").update_u32_le(7);
+    assert_eq!(chained.finalize(), sequential.finalize());
+
+    assert_eq!(
+        Hasher::<KT128>::new().update(b"foo").clone().finalize(),
+        hash::<KT128>(b"foo")
+    );
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_hash_file_with_size() {
+    use crate::hash_file_with_size;
+
+    let dir = std::env::temp_dir();
+    let short_path = dir.join(format!("marsupial-test-{}-short", std::process::id()));
+    let long_path = dir.join(format!("marsupial-test-{}-long", std::process::id()));
+    std::fs::write(&short_path, b"foobar").unwrap();
+    std::fs::write(&long_path, b"foobarbaz").unwrap();
+
+    let short = hash_file_with_size::<KT128, _>(&short_path).unwrap();
+    let long = hash_file_with_size::<KT128, _>(&long_path).unwrap();
+    std::fs::remove_file(&short_path).unwrap();
+    std::fs::remove_file(&long_path).unwrap();
+
+    assert_ne!(short, long);
+    let mut expected = Hasher::<KT128>::new();
+    expected.update(6u64.to_le_bytes()).update(b"foobar");
+    assert_eq!(short, expected.finalize());
+    assert!(hash_file_with_size::<KT128, _>(&short_path).is_err());
+}
+
+#[test]
+fn test_block_iter() {
+    let mut hasher = Hasher::<KT256>::new();
+    hasher.update(b"foo");
+    let mut expected = [0; 48];
+    hasher.clone().finalize_xof().squeeze(&mut expected);
+
+    let blocks: Vec<[u8; 16]> = hasher.finalize_xof().block_iter().take(3).collect();
+    assert_eq!(blocks.concat(), expected);
+}
+
+#[cfg(feature = "digest")]
+#[test]
+fn test_dual_hasher() {
+    use crate::DualHasher;
+    use sha3::Shake128;
+
+    let mut input = vec![0; 20_000];
+    fill_pattern(&mut input);
+
+    let mut hasher = DualHasher::<KT128, _>::new(Shake128::default());
+    for chunk in input.chunks(1000) {
+        hasher.update(chunk);
+    }
+    let (k12, shake) = hasher.finalize();
+    assert_eq!(k12, hash::<KT128>(&input));
+
+    let mut shake_output = [0; 32];
+    shake.finalize_xof().read(&mut shake_output);
+    let mut expected = [0; 32];
+    Shake128::default()
+        .chain(&input)
+        .finalize_xof()
+        .read(&mut expected);
+    assert_eq!(shake_output, expected);
+}
+
+#[test]
+fn test_hash_parsing() {
+    use crate::HashParseError;
+
+    let digest = hash::<KT128>(b"foo");
+    let hex = hex::encode(digest.as_bytes());
+    assert_eq!(Hash::from_hex(&hex), Ok(digest));
+    assert_eq!(Hash::from_hex(&hex.to_uppercase()), Ok(digest));
+    assert_eq!(hex.parse::<Hash<32>>(), Ok(digest));
+    assert_eq!(Hash::parse_any(&hex), Ok(digest));
+
+    assert_eq!(
+        Hash::<32>::from_hex(&hex[..62]),
+        Err(HashParseError::InvalidLength {
+            expected: 32,
+            actual: 31
+        })
+    );
+    assert_eq!(
+        Hash::<32>::from_hex(&hex[..63]),
+        Err(HashParseError::InvalidEncoding)
+    );
+    assert_eq!(
+        Hash::<32>::from_hex(&"g".repeat(64)),
+        Err(HashParseError::InvalidEncoding)
+    );
+    assert_eq!(
+        Hash::<32>::parse_any("not a hash"),
+        Err(HashParseError::InvalidEncoding)
+    );
+    assert_eq!(
+        HashParseError::InvalidLength {
+            expected: 32,
+            actual: 31
+        }
+        .to_string(),
+        "expected the hash to decode to 32 bytes, but it decoded to 31"
+    );
+}
+
+#[test]
+fn test_to_hex_bytes() {
+    let hex: [u8; 64] = hash::<KT128>(&[]).to_hex_bytes();
+    assert_eq!(
+        &hex,
+        b"1ac2d450fc3b4205d19da7bfca1b37513c0803577ac7167f06fe2ce1f0ef39e5"
+    );
+
+    let digest = hash::<KT256>(b"foo");
+    let hex: [u8; 128] = digest.to_hex_bytes();
+    assert_eq!(hex::encode(digest.as_bytes()).as_bytes(), &hex[..]);
+    #[cfg(feature = "alloc")]
+    assert_eq!(digest.to_hex().as_bytes(), &hex[..]);
+    assert_eq!(
+        Ok(digest),
+        Hash::from_hex(core::str::from_utf8(&hex).unwrap())
+    );
+}
+
+#[test]
+fn test_hash_try_from_str() {
+    use crate::HashParseError;
+
+    let digest = hash::<KT128>(b"foo");
+    let hex = hex::encode(digest.as_bytes());
+    let upper = hex.to_uppercase();
+    let invalid = "g".repeat(64);
+    for s in [&hex[..], &upper, &hex[..62], &hex[..63], &invalid, ""] {
+        assert_eq!(s.parse::<Hash<32>>(), s.try_into());
+    }
+
+    let parsed: Result<Hash<32>, HashParseError> = hex.as_str().try_into();
+    assert_eq!(parsed, Ok(digest));
+    assert_eq!(
+        Hash::<32>::try_from(&hex[..62]),
+        Err(HashParseError::InvalidLength {
+            expected: 32,
+            actual: 31
+        })
+    );
+    assert_eq!(
+        Hash::<32>::try_from(invalid.as_str()),
+        Err(HashParseError::InvalidEncoding)
+    );
+}
+
+#[cfg(feature = "base64")]
+#[test]
+fn test_hash_parsing_base64() {
+    use crate::HashParseError;
+    use base64::{engine::general_purpose, Engine};
+
+    let digest = hash::<KT128>(b"foo");
+    for engine in [
+        general_purpose::STANDARD,
+        general_purpose::STANDARD_NO_PAD,
+        general_purpose::URL_SAFE,
+        general_purpose::URL_SAFE_NO_PAD,
+    ] {
+        let encoded = engine.encode(digest.as_bytes());
+        assert_eq!(Hash::parse_any(&encoded), Ok(digest));
+    }
+
+    assert_eq!(
+        Hash::<32>::parse_any(&general_purpose::STANDARD.encode([0; 16])),
+        Err(HashParseError::InvalidLength {
+            expected: 32,
+            actual: 16
+        })
+    );
+    // this is valid hexadecimal as well as valid base64, and the former wins
+    let ambiguous = "ab".repeat(32);
+    assert_eq!(
+        Hash::<32>::parse_any(&ambiguous),
+        Ok(Hash::from([0xab; 32]))
+    );
+    // this is malformed hexadecimal, which would decode to 32 bytes as base64
+    let truncated = &digest.to_hex()[..43];
+    assert!(Hash::<32>::parse_any(truncated).is_err());
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_verify_reader() {
+    use crate::verify_reader;
+    use std::io::Cursor;
+
+    let mut input = vec![0; 50_000];
+    fill_pattern(&mut input);
+    let expected = hash::<KT256>(&input);
+
+    assert!(verify_reader::<KT256, _>(Cursor::new(&input), expected.as_bytes()).unwrap());
+    assert!(!verify_reader::<KT256, _>(Cursor::new(&input[1..]), expected.as_bytes()).unwrap());
+    assert!(!verify_reader::<KT256, _>(Cursor::new(&input), &expected.as_bytes()[..32]).unwrap());
+}
+
+#[test]
+fn test_update_frame() {
+    let mut ab_c = Hasher::<KT128>::new();
+    ab_c.update_frame(b"ab").update_frame(b"c");
+    let mut a_bc = Hasher::<KT128>::new();
+    a_bc.update_frame(b"a").update_frame(b"bc");
+    let digest = ab_c.finalize();
+    assert_ne!(digest, a_bc.finalize());
+
+    let mut framed = FramedHasher::<KT128>::new();
+    framed.push(b"ab");
+    framed.push(b"c");
+    assert_eq!(digest, framed.finalize());
+}
+
+#[test]
+fn test_try_finalize_xof() {
+    let mut expected = [0; 64];
+    Hasher::<KT128>::new()
+        .update(b"foo")
+        .clone()
+        .finalize_custom_xof(b"bar")
+        .squeeze(&mut expected);
+
+    let mut hasher = Hasher::<KT128>::new();
+    hasher.update(b"foo");
+    let mut output = [0; 64];
+    hasher
+        .try_finalize_custom_xof(b"bar")
+        .expect("finalization failed")
+        .squeeze(&mut output);
+    assert_eq!(expected, output);
+
+    let reader = Hasher::<KT128>::new().finalize_xof();
+    let hasher = Hasher::<KT128>(reader.0, std::marker::PhantomData);
+    assert_eq!(
+        hasher.try_finalize_xof().map(|_| ()),
+        Err(crate::K12Error::new(1))
+    );
+}
+
+#[test]
+fn test_hash_with_prefix_and_suffix() {
+    use crate::{hash_with_prefix, hash_with_suffix};
+
+    let mut input = [0; CHUNK_SIZE + 1];
+    fill_pattern(&mut input);
+    let (head, tail) = input.split_at(17);
+    assert_eq!(hash_with_prefix::<KT128>(head, tail), hash::<KT128>(&input));
+    assert_eq!(hash_with_suffix::<KT256>(head, tail), hash::<KT256>(&input));
+    assert_eq!(
+        hash_with_prefix::<KT128>(b"", b"foo"),
+        hash::<KT128>(b"foo")
+    );
+    assert_eq!(
+        hash_with_suffix::<KT128>(b"foo", b""),
+        hash::<KT128>(b"foo")
+    );
+}
+
+//NOTE: this only uses functionality available without `std` or `alloc`, so it
+//      also covers `cargo test --no-default-features`
+#[test]
+fn test_core_api() {
+    let digest = hash::<KT128>(b"foobarbaz");
+
+    let mut hasher = Hasher::<KT128>::new();
+    hasher.update(b"foo").update(b"bar").update(b"baz");
+    let mut output = [0; 64];
+    hasher.finalize_xof().squeeze(&mut output);
+    assert_eq!(&output[..32], digest.as_bytes());
+
+    let parsed: Hash<4> = "0123abcd".parse().expect("unable to parse the hash");
+    assert!(parsed.ct_eq(&Hash::from([0x01, 0x23, 0xab, 0xcd])));
+}
+
+#[test]
+fn test_is_zero() {
+    assert!(Hash::<32>::default().is_zero());
+    assert!(Hash::<64>::default().is_zero());
+    assert!(!hash::<KT128>(b"foo").is_zero());
+
+    let mut bytes = [0; 32];
+    bytes[31] = 1;
+    assert!(!Hash::from(bytes).is_zero());
+}
+
+#[test]
+fn test_empty_customization() {
+    let expected = "1ac2d450fc3b4205d19da7bfca1b37513c0803577ac7167f06fe2ce1f0ef39e5";
+    let finalized = Hasher::<KT128>::new().finalize();
+    assert_eq!(finalized, Hasher::<KT128>::new().finalize_custom(&[]));
+    assert_eq!(expected, hex::encode(finalized.as_bytes()));
+
+    let expected = "b23d2e9cea9f4904e02bec06817fc10ce38ce8e93ef4c89e6537076af8646404e3e8b68107b8833a5d30490aa33482353fd4adc7148ecb782855003aaebde4a9";
+    let finalized = Hasher::<KT256>::new().finalize();
+    assert_eq!(finalized, Hasher::<KT256>::new().finalize_custom(&[]));
+    assert_eq!(expected, hex::encode(finalized.as_bytes()));
+}
+
+#[test]
+fn test_finalize_into_hash() {
+    let mut out = Hash::from([0xff; 32]);
+    let mut hasher = Hasher::<KT128>::new();
+    hasher.update(b"foo");
+    hasher.clone().finalize_custom_into_hash(b"bar", &mut out);
+    assert_eq!(out, hasher.clone().finalize_custom(b"bar"));
+    hasher.clone().finalize_into_hash(&mut out);
+    assert_eq!(out, hasher.finalize());
+
+    let mut out = Hash::default();
+    Hasher::<KT256>::new().finalize_custom_into_hash(b"bar", &mut out);
+    assert_eq!(out, Hasher::<KT256>::new().finalize_custom(b"bar"));
+}
+
+#[test]
+fn test_oneshot() {
+    let (digest, mut output_reader) = Hasher::<KT128>::oneshot(b"foo", b"bar");
+    assert_eq!(0, output_reader.position());
+    let mut output = [0; 64];
+    output_reader.squeeze(&mut output);
+    assert_eq!(digest.as_bytes(), &output[..32]);
+
+    let mut hasher = Hasher::<KT128>::new();
+    hasher.update(b"foo");
+    assert_eq!(digest, hasher.finalize_custom(b"bar"));
+
+    let (digest, mut output_reader) = Hasher::<KT256>::oneshot(b"foo", &[]);
+    let mut output = [0; 64];
+    output_reader.squeeze(&mut output);
+    assert_eq!(digest.as_bytes(), &output[..]);
+    assert_eq!(digest, hash::<KT256>(b"foo"));
+}
+
+#[test]
+fn test_check_dispatch() {
+    crate::self_test::check_dispatch();
+}
+
+#[test]
+fn test_selftest() {
+    assert_eq!(Ok(()), crate::selftest());
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_into_boxed_slice() {
+    let digest = hash::<KT256>(b"foo");
+    let boxed = digest.into_boxed_slice();
+    assert_eq!(64, boxed.len());
+    assert_eq!(&digest.as_bytes()[..], &boxed[..]);
+}
+
+#[test]
+fn test_limited_hasher() {
+    use crate::{LimitExceededError, LimitedHasher};
+
+    let mut hasher = LimitedHasher::<KT128>::new(6);
+    hasher.update(b"foo").unwrap().update(b"ba").unwrap();
+    assert_eq!(1, hasher.remaining());
+    assert_eq!(
+        LimitExceededError::new(6),
+        hasher.update(b"rbaz").unwrap_err()
+    );
+    assert_eq!(5, hasher.len());
+    hasher.update(b"r").unwrap();
+    assert!(hasher.update(b"").is_ok());
+    assert!(hasher.update(b"b").is_err());
+    assert_eq!(hasher.finalize(), hash::<KT128>(b"foobar"));
+}
+
+#[test]
+fn test_as_u64_lanes() {
+    let digest = hash::<KT128>(b"foo");
+    let le: [u64; 4] = digest.as_u64_le();
+    let be: [u64; 4] = digest.as_u64_be();
+    for (i, bytes) in digest.as_bytes().chunks_exact(8).enumerate() {
+        assert_eq!(le[i], u64::from_le_bytes(bytes.try_into().unwrap()));
+        assert_eq!(be[i], u64::from_be_bytes(bytes.try_into().unwrap()));
+    }
+
+    let digest = hash::<KT256>(b"foo");
+    let le = digest.as_u64_le::<8>();
+    let be = digest.as_u64_be::<8>();
+    for i in 0..8 {
+        assert_eq!(le[i], be[i].swap_bytes());
+    }
+    assert_eq!(&le[0].to_le_bytes(), &digest.as_bytes()[..8]);
+    assert_eq!(&be[7].to_be_bytes(), &digest.as_bytes()[56..]);
+}
+
+#[test]
+fn test_finalize_with() {
+    let mut hasher = Hasher::<KT128>::new();
+    hasher.update(b"foo");
+    let expected: u32 = hasher
+        .clone()
+        .finalize()
+        .as_bytes()
+        .iter()
+        .map(|&b| b as u32)
+        .sum();
+    let sum = hasher
+        .clone()
+        .finalize_with(|bytes| bytes.iter().map(|&b| b as u32).sum::<u32>());
+    assert_eq!(expected, sum);
+
+    let len = hasher.finalize_custom_with(b"bar", |bytes| bytes.len());
+    assert_eq!(32, len);
+    assert!(Hasher::<KT256>::new().finalize_with(|bytes| bytes == hash::<KT256>(b"").as_bytes()));
+}
+
+#[cfg(feature = "rkyv")]
+#[test]
+fn test_rkyv_round_trip() {
+    use rkyv::rancor::Error;
+
+    let digest = hash::<KT256>(b"foo");
+    let bytes = rkyv::to_bytes::<Error>(&digest).unwrap();
+    assert_eq!(digest.as_bytes(), &bytes[..]);
+
+    let archived = rkyv::access::<Hash<64>, Error>(&bytes).unwrap();
+    assert_eq!(&digest, archived);
+    assert_eq!(
+        digest,
+        rkyv::deserialize::<Hash<64>, Error>(archived).unwrap()
+    );
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_update_path() {
+    use crate::PathEncodingError;
+    use std::path::Path;
+
+    let path_hash = |path: &str| {
+        let mut hasher = Hasher::<KT128>::new();
+        hasher
+            .update_path(Path::new(path))
+            .map(|hasher| hasher.clone().finalize())
+    };
+
+    let mut expected = Hasher::<KT128>::new();
+    expected
+        .update_u64_le(2)
+        .update_frame(b"foo")
+        .update_frame(b"bar");
+    let expected = expected.finalize();
+    assert_eq!(Ok(expected), path_hash("foo/bar"));
+    assert_eq!(Ok(expected), path_hash("./foo//bar/"));
+    assert_ne!(Ok(expected), path_hash("foob/ar"));
+    assert_ne!(Ok(expected), path_hash("foo/bar/baz"));
+    assert_eq!(
+        Ok(Hasher::<KT128>::new().update_u64_le(0).clone().finalize()),
+        path_hash("")
+    );
+
+    assert_eq!(
+        Err(PathEncodingError::UnsupportedComponent),
+        path_hash("/foo")
+    );
+    assert_eq!(
+        Err(PathEncodingError::UnsupportedComponent),
+        path_hash("../foo")
+    );
+}
+
+fn check_finalize_matches_xof<N: crate::SecurityLevel>() {
+    use crate::HashContainer;
+
+    for len in [
+        0,
+        1,
+        17,
+        CHUNK_SIZE - 1,
+        CHUNK_SIZE,
+        CHUNK_SIZE + 1,
+        3 * CHUNK_SIZE + 5,
+    ] {
+        let mut input = vec![0; len];
+        fill_pattern(&mut input);
+        for customization_len in [0, 1, 41, 300] {
+            let mut customization = vec![0; customization_len];
+            fill_pattern(&mut customization);
+            customization.reverse();
+
+            let mut hasher = Hasher::<N>::new();
+            hasher.update(&input);
+            let mut output = vec![0; N::HASH_ARRAY_LENGTH];
+            hasher
+                .clone()
+                .finalize_custom_xof(&customization)
+                .squeeze(&mut output);
+            assert_eq!(
+                output,
+                hasher.finalize_custom(&customization).as_slice(),
+                "mismatch for {} bits, input length {}, customization length {}",
+                N::BITS,
+                len,
+                customization_len
+            );
+        }
+    }
+}
+
+#[test]
+fn test_finalize_matches_xof_prefix() {
+    check_finalize_matches_xof::<KT128>();
+    check_finalize_matches_xof::<KT256>();
+}
+
+#[cfg(feature = "unstable-internals")]
+#[test]
+fn test_hasher_state_eq() {
+    let mut whole = Hasher::<KT128>::new();
+    whole.update(b"foobar");
+    let mut split = Hasher::<KT128>::new();
+    split.update(b"foo").update(b"bar");
+    assert_eq!(whole, split);
+    assert_eq!(whole, whole.clone());
+
+    split.update(b"");
+    assert_eq!(whole, split);
+    split.update(b"baz");
+    assert_ne!(whole, split);
+
+    let mut input = [0; 3 * CHUNK_SIZE + 5];
+    fill_pattern(&mut input);
+    let mut whole = Hasher::<KT256>::new();
+    whole.update(input);
+    let mut split = Hasher::<KT256>::new();
+    for piece in input.chunks(1000) {
+        split.update(piece);
+    }
+    assert_eq!(whole, split);
+}
+
+#[cfg(feature = "futures")]
+#[test]
+fn test_hash_sink() {
+    use bytes::Bytes;
+    use futures::{executor::block_on, SinkExt};
+
+    let (mut sink, output) = Hasher::<KT256>::sink();
+    let digest = block_on(async {
+        sink.send(Bytes::from_static(b"foo")).await.unwrap();
+        sink.send(Bytes::from(b"bar".to_vec())).await.unwrap();
+        sink.close().await.unwrap();
+        output.await
+    });
+    assert_eq!(Ok(hash::<KT256>(b"foobar")), digest);
+
+    let (sink, output) = Hasher::<KT128>::sink();
+    drop(sink);
+    assert_eq!(Err(crate::SinkCanceledError), block_on(output));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_squeeze_append() {
+    let mut expected = [0; 64];
+    Hasher::<KT128>::new().finalize_xof().squeeze(&mut expected);
+
+    let mut output_reader = Hasher::<KT128>::new().finalize_xof();
+    let mut output = vec![];
+    output_reader.squeeze_append(&mut output, 32);
+    output_reader.squeeze_append(&mut output, 0);
+    output_reader.squeeze_append(&mut output, 32);
+    assert_eq!(&expected[..], &output[..]);
+    assert_eq!(64, output_reader.position());
+
+    let mut output = b"foo".to_vec();
+    Hasher::<KT128>::new()
+        .finalize_xof()
+        .squeeze_append(&mut output, 64);
+    assert_eq!(b"foo", &output[..3]);
+    assert_eq!(&expected[..], &output[3..]);
+}
+
+#[test]
+fn test_hasher_builder() {
+    use crate::HasherBuilder;
+
+    let mut input = [0; 5000];
+    fill_pattern(&mut input);
+
+    let builder = HasherBuilder::<KT128>::new();
+    let mut output = [0; 32];
+    builder
+        .build()
+        .update(input)
+        .clone()
+        .finalize_into(&mut output);
+    assert_eq!(&output, hash::<KT128>(&input).as_bytes());
+
+    for customization in [&b""[..], b"ctx", &input[..300]] {
+        for output_len in [0, 1, 32, 48, 64, 200] {
+            let builder = HasherBuilder::<KT256>::new()
+                .customization(customization)
+                .output_len(output_len)
+                .capacity_hint(input.len());
+            let mut hasher = builder.build();
+            assert_eq!(output_len, hasher.output_len());
+            hasher.update(&input[..1000]).update(&input[1000..]);
+
+            let mut output = vec![0; output_len];
+            hasher.clone().finalize_into(&mut output);
+
+            let mut expected = vec![0; output_len];
+            let mut reference = Hasher::<KT256>::new();
+            reference.update(input);
+            reference
+                .clone()
+                .finalize_custom_xof(customization)
+                .squeeze(&mut expected);
+            assert_eq!(expected, output);
+
+            #[cfg(feature = "alloc")]
+            assert_eq!(expected, hasher.clone().finalize());
+
+            let mut output = [0; 64];
+            hasher.finalize_xof().squeeze(&mut output);
+            assert_eq!(reference.finalize_custom(customization), output);
+        }
+    }
+}
+
+#[test]
+#[should_panic(expected = "the output buffer doesn't match the configured output length")]
+fn test_hasher_builder_wrong_output_len() {
+    let hasher = crate::HasherBuilder::<KT128>::new().output_len(48).build();
+    hasher.finalize_into(&mut [0; 32]);
+}
+
+#[test]
+fn test_fold() {
+    let digest = hash::<KT128>(b"foobarbaz");
+    assert_eq!(&digest.as_bytes()[..16], digest.truncate::<16>().as_bytes());
+    assert_eq!(digest, digest.truncate::<32>());
+    assert_ne!(digest.fold::<16>(), digest.truncate::<16>());
+    assert_eq!(digest.fold::<16>(), digest.fold::<16>());
+    assert_eq!(
+        digest.fold::<16>(),
+        hash::<KT128>(digest.as_bytes()).truncate::<16>()
+    );
+
+    //NOTE: changing a byte past the truncation point changes the folded value
+    let mut bytes = *digest.as_bytes();
+    bytes[31] ^= 1;
+    let other = Hash::from(bytes);
+    assert_eq!(digest.truncate::<16>(), other.truncate::<16>());
+    assert_ne!(digest.fold::<16>(), other.fold::<16>());
+
+    let digest = hash::<KT256>(b"foobarbaz");
+    assert_ne!(digest.fold::<16>(), digest.truncate::<16>());
+    assert_eq!(digest.fold::<64>(), hash::<KT256>(digest.as_bytes()));
+    assert_eq!(
+        &digest.fold::<100>().as_bytes()[..64],
+        hash::<KT256>(digest.as_bytes()).as_bytes()
+    );
+}
+
+#[test]
+fn test_struct_hasher() {
+    use crate::StructHasher;
+
+    let mut hasher = StructHasher::<KT128>::new();
+    hasher.field(b"a", b"bc").field(b"d", b"");
+    let digest = hasher.clone().finish();
+
+    let mut other = StructHasher::<KT128>::new();
+    other.field(b"a", b"b").field(b"cd", b"");
+    assert_ne!(digest, other.finish());
+
+    let mut other = StructHasher::<KT128>::new();
+    other.field(b"ab", b"c").field(b"d", b"");
+    assert_ne!(digest, other.finish());
+
+    let mut other = StructHasher::<KT128>::new();
+    other.field(b"a", b"bcd");
+    assert_ne!(digest, other.finish());
+
+    let mut reference = Hasher::<KT128>::new();
+    reference
+        .update_frame(b"a")
+        .update_frame(b"bc")
+        .update_frame(b"d")
+        .update_frame(b"");
+    assert_eq!(digest, reference.finalize());
+    assert_eq!(hash::<KT256>(&[]), StructHasher::<KT256>::new().finish());
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_write() {
+    use std::io::{self, Write};
+
+    fn write_all_of(mut writer: impl Write, input: &[u8]) -> io::Result<()> {
+        writer.write_all(input)?;
+        writer.flush()
+    }
+
+    let mut input = vec![0; 3 * CHUNK_SIZE + 5];
+    fill_pattern(&mut input);
+
+    let mut hasher = Hasher::<KT256>::new();
+    write_all_of(&mut hasher, &input[..100]).unwrap();
+    io::copy(&mut &input[100..], &mut hasher).unwrap();
+    assert_eq!(hash::<KT256>(&input), hasher.clone().finalize());
+
+    write_all_of(hasher.clone(), b"").unwrap();
+    assert_eq!(hash::<KT256>(&input), hasher.finalize());
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_shuffle_indices() {
+    use crate::shuffle_indices;
+
+    assert!(shuffle_indices::<KT128>(b"seed", 0).is_empty());
+    assert_eq!(vec![0], shuffle_indices::<KT128>(b"seed", 1));
+
+    for n in [2, 10, 1000] {
+        let permutation = shuffle_indices::<KT128>(b"seed", n);
+        assert_eq!(permutation, shuffle_indices::<KT128>(b"seed", n));
+        if n > 2 {
+            assert_ne!(permutation, shuffle_indices::<KT128>(b"other seed", n));
+            assert_ne!(permutation, shuffle_indices::<KT256>(b"seed", n));
+        }
+
+        let mut sorted = permutation.clone();
+        sorted.sort_unstable();
+        assert_eq!((0..n).collect::<Vec<_>>(), sorted);
+    }
+
+    //NOTE: the first swap of a shuffle of 0..3 draws from 0..=2
+    let mut output = [0; 8];
+    Hasher::<KT128>::new()
+        .update(b"seed")
+        .clone()
+        .finalize_xof()
+        .squeeze(&mut output);
+    let j = (u64::from_le_bytes(output) % 3) as usize;
+    let mut expected = [0, 1, 2];
+    expected.swap(2, j);
+    assert_eq!(expected[2], shuffle_indices::<KT128>(b"seed", 3)[2]);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_read_vectored() {
+    use std::io::{IoSliceMut, Read};
+
+    let mut expected = [0; 300];
+    Hasher::<KT128>::new()
+        .update(b"foobarbaz")
+        .clone()
+        .finalize_xof()
+        .squeeze(&mut expected);
+
+    let mut output_reader = Hasher::<KT128>::new()
+        .update(b"foobarbaz")
+        .clone()
+        .finalize_xof();
+    let (mut first, mut second, mut third) = ([0; 100], [0; 0], [0; 150]);
+    let len = output_reader
+        .read_vectored(&mut [
+            IoSliceMut::new(&mut first),
+            IoSliceMut::new(&mut second),
+            IoSliceMut::new(&mut third),
+        ])
+        .unwrap();
+    assert_eq!(250, len);
+    assert_eq!(250, output_reader.position());
+    assert_eq!(&expected[..100], &first[..]);
+    assert_eq!(&expected[100..250], &third[..]);
+
+    let mut rest = [0; 50];
+    output_reader.read_exact(&mut rest).unwrap();
+    assert_eq!(&expected[250..], &rest[..]);
+}
+
+#[test]
+fn test_update_prefix() {
+    let mut input = [0; 3 * CHUNK_SIZE];
+    fill_pattern(&mut input);
+
+    let mut hasher = Hasher::<KT128>::new();
+    let mut rest = &input[..];
+    for n in [0, 5, CHUNK_SIZE, 17] {
+        let len = rest.len();
+        rest = hasher.update_prefix(rest, n);
+        assert_eq!(len - n, rest.len());
+    }
+    assert_eq!(&input[5 + CHUNK_SIZE + 17..], rest);
+    assert_eq!(
+        hash::<KT128>(&input[..5 + CHUNK_SIZE + 17]),
+        hasher.clone().finalize()
+    );
+
+    assert!(hasher.update_prefix(rest, rest.len()).is_empty());
+    assert_eq!(hash::<KT128>(&input), hasher.finalize());
+}
+
+#[test]
+#[should_panic]
+fn test_update_prefix_out_of_bounds() {
+    let _ = Hasher::<KT128>::new().update_prefix(b"foo", 4);
+}
+
+#[test]
+fn test_new_output_buffer() {
+    fn digest_custom<N: crate::SecurityLevel>(input: &[u8], customization: &[u8]) -> N::Hash {
+        let mut out = N::new_output_buffer();
+        assert_eq!(N::Hash::default(), out);
+
+        let mut hasher = Hasher::<N>::new();
+        hasher.update(input);
+        hasher.finalize_custom_into_hash(customization, &mut out);
+        out
+    }
+
+    assert!(<KT128 as crate::SecurityLevel>::new_output_buffer().is_zero());
+    assert!(<KT256 as crate::SecurityLevel>::new_output_buffer().is_zero());
+    assert_eq!(hash::<KT128>(b"foo"), digest_custom::<KT128>(b"foo", b""));
+    assert_eq!(
+        Hasher::<KT256>::new()
+            .update(b"foo")
+            .clone()
+            .finalize_custom(b"bar"),
+        digest_custom::<KT256>(b"foo", b"bar")
+    );
+}
+
+#[test]
+fn test_squeeze_xor() {
+    let mut hasher = Hasher::<KT256>::new();
+    hasher.update(b"key and nonce");
+    let output_reader = hasher.finalize_xof();
+
+    let mut original = vec![0; 10000];
+    fill_pattern(&mut original);
+
+    let mut data = original.clone();
+    let mut first = output_reader.clone();
+    first.squeeze_xor(&mut data[..100]);
+    first.squeeze_xor(&mut data[100..]);
+    assert_eq!(10000, first.position());
+    assert_ne!(original, data);
+
+    let mut keystream = vec![0; 10000];
+    output_reader.clone().squeeze(&mut keystream);
+    for ((byte, original), key) in data.iter().zip(&original).zip(&keystream) {
+        assert_eq!(original ^ key, *byte);
+    }
+
+    output_reader.clone().squeeze_xor(&mut data);
+    assert_eq!(original, data);
+
+    let mut empty = output_reader.clone();
+    empty.squeeze_xor(&mut []);
+    assert_eq!(0, empty.position());
+}
+
+#[test]
+fn test_new_with_prefix() {
+    let mut hasher = Hasher::<KT128>::new_with_prefix(b"key");
+    hasher.update(b"message");
+    let prefixed = hasher.finalize();
+    assert_eq!(hash::<KT128>(b"keymessage"), prefixed);
+    assert_eq!(
+        crate::hash_with_prefix::<KT128>(b"key", b"message"),
+        prefixed
+    );
+
+    //NOTE: the customization string goes after the input, along with its
+    //      length, so it isn't the same as a prefix (or as a suffix)
+    let mut hasher = Hasher::<KT128>::new();
+    hasher.update(b"message");
+    let customized = hasher.finalize_custom(b"key");
+    assert_ne!(prefixed, customized);
+    assert_ne!(hash::<KT128>(b"messagekey"), customized);
+
+    //NOTE: for inputs of a single chunk, what's hashed is a single
+    //      TurboSHAKE call over the input, the customization string, and its
+    //      length (3, in one byte, followed by the length of that encoding)
+    let mut reference = [0; 32];
+    reference_turboshake(32, b"messagekey\x03\x01", 0x07, &mut reference);
+    assert_eq!(&reference, customized.as_bytes());
+
+    let mut hasher = Hasher::<KT256>::new_with_prefix(b"");
+    hasher.update(b"message");
+    assert_eq!(hash::<KT256>(b"message"), hasher.finalize());
+}
+
+//NOTE: every entry point is checked with empty input (and an empty
+//      customization string), as empty slices have dangling pointers that
+//      would be easy to mishandle in any one of them
+mod empty_input {
+    use crate::{
+        hash, hash_iter, hash_slices, hash_with_prefix, hash_with_suffix, FramedHasher, Hasher,
+        LimitedHasher, SecurityLevel, KT128, KT256,
+    };
+
+    const KT128_EMPTY: &str = "1ac2d450fc3b4205d19da7bfca1b37513c0803577ac7167f06fe2ce1f0ef39e5";
+    const KT256_EMPTY: &str = "b23d2e9cea9f4904e02bec06817fc10ce38ce8e93ef4c89e6537076af8646404e3e8b68107b8833a5d30490aa33482353fd4adc7148ecb782855003aaebde4a9";
+
+    fn check_hash_functions<N: SecurityLevel>(expected: &str) {
+        use crate::HashContainer;
+
+        let expected = hex::decode(expected).unwrap();
+        assert_eq!(expected, hash::<N>(&[]).as_slice());
+        assert_eq!(expected, hash_slices::<N>(&[]).as_slice());
+        assert_eq!(expected, hash_slices::<N>(&[&[], &[]]).as_slice());
+        assert_eq!(expected, hash_iter::<N, _>(core::iter::empty()).as_slice());
+        assert_eq!(expected, hash_with_prefix::<N>(&[], &[]).as_slice());
+        assert_eq!(expected, hash_with_suffix::<N>(&[], &[]).as_slice());
+    }
+
+    fn check_hasher<N: SecurityLevel>(expected: &str) {
+        use crate::HashContainer;
+
+        let expected = hex::decode(expected).unwrap();
+        let digest = Hasher::<N>::new().finalize();
+        assert_eq!(expected, digest.as_slice());
+        assert_eq!(
+            digest,
+            Hasher::<N>::new()
+                .update([])
+                .update_str("")
+                .clone()
+                .finalize()
+        );
+        assert_eq!(digest, Hasher::<N>::new().finalize_custom(&[]));
+        assert_eq!(digest, Hasher::<N>::with_capacity_hint(0).finalize());
+        assert!(Hasher::<N>::new().finalize_verify(&expected));
+        assert!(Hasher::<N>::new().finalize_with(|bytes| bytes == &expected[..]));
+
+        let mut out = N::Hash::default();
+        Hasher::<N>::new().finalize_into_hash(&mut out);
+        assert_eq!(digest, out);
+
+        let (other, _) = Hasher::<N>::new().digest_and_xof();
+        assert_eq!(digest, other);
+        let (other, _) = Hasher::<N>::oneshot(&[], &[]);
+        assert_eq!(digest, other);
+
+        let mut output = vec![0; N::HASH_ARRAY_LENGTH];
+        let mut output_reader = Hasher::<N>::new().try_finalize_xof().unwrap();
+        output_reader.squeeze(&mut []);
+        assert!(output_reader.squeeze_uninit(&mut []).is_empty());
+        output_reader.discard(0);
+        assert_eq!(0, output_reader.position());
+        output_reader.squeeze(&mut output);
+        assert_eq!(expected, output);
+
+        assert_eq!(digest, FramedHasher::<N>::new().finalize());
+        assert_eq!(digest, LimitedHasher::<N>::new(0).finalize());
+    }
+
+    #[test]
+    fn test_hash_functions() {
+        check_hash_functions::<KT128>(KT128_EMPTY);
+        check_hash_functions::<KT256>(KT256_EMPTY);
+    }
+
+    #[test]
+    fn test_hasher() {
+        check_hasher::<KT128>(KT128_EMPTY);
+        check_hasher::<KT256>(KT256_EMPTY);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_alloc() {
+        use crate::{hash_hex, hash_multi_len, Hash};
+
+        assert_eq!(KT128_EMPTY, hash_hex::<KT128>(&[]));
+        assert_eq!(KT256_EMPTY, hash_hex::<KT256>(&[]));
+        assert_eq!(
+            vec![vec![], hex::decode(KT128_EMPTY).unwrap()],
+            hash_multi_len::<KT128>(&[], &[0, 32])
+        );
+        assert_eq!(
+            hash::<KT128>(&[]),
+            Hasher::<KT128>::new().finalize_custom_slices(&[])
+        );
+
+        let mut output = vec![];
+        let mut output_reader = Hasher::<KT256>::new().finalize_xof();
+        output_reader.squeeze_append(&mut output, 0);
+        assert!(output.is_empty());
+        output_reader.squeeze_append(&mut output, 64);
+        assert_eq!(KT256_EMPTY, hex::encode(output));
+        assert_eq!(
+            Hash::<32>::from_hex(""),
+            Err(crate::HashParseError::InvalidLength {
+                expected: 32,
+                actual: 0
+            })
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_std() {
+        use crate::{copy_and_hash, verify_reader};
+
+        let mut writer = vec![];
+        let (len, digest) = copy_and_hash::<KT128, _, _>(&mut &[][..], &mut writer).unwrap();
+        assert_eq!((0, KT128_EMPTY), (len, &*digest.to_hex()));
+        assert!(writer.is_empty());
+        assert!(verify_reader::<KT256, _>(&[][..], &hex::decode(KT256_EMPTY).unwrap()).unwrap());
+    }
+}
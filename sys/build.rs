@@ -5,7 +5,7 @@
 //! changes when we re-vendor upstream code.
 
 use bindgen::callbacks::ParseCallbacks;
-use std::{env, path::PathBuf};
+use std::{env, path::PathBuf, process::Command};
 
 #[derive(Debug)]
 struct ParseDoxygen;
@@ -25,23 +25,38 @@ enum TargetImplementation {
     Armv8Asha3,
 }
 
+impl TargetImplementation {
+    /// The name the implementation is exposed under at runtime
+    fn name(&self) -> &'static str {
+        match self {
+            TargetImplementation::Optimized64 => "Optimized64",
+            TargetImplementation::Optimized64NoAsm => "Optimized64NoAsm",
+            TargetImplementation::Plain64 => "Plain64",
+            TargetImplementation::Inplace32BI => "Inplace32BI",
+            TargetImplementation::Armv8Asha3 => "ARMv8Asha3",
+        }
+    }
+}
+
 fn main() {
     let target_arch = env::var("CARGO_CFG_TARGET_ARCH").unwrap();
     let target_os = env::var("CARGO_CFG_TARGET_OS").unwrap();
     let target_pointer_width = env::var("CARGO_CFG_TARGET_POINTER_WIDTH").unwrap();
-    let target_env = env::var("CARGO_CFG_TARGET_ENV").unwrap(); // e.g. "msvc" on Windows
-    let target_is_little_endian = match env::var("CARGO_CFG_TARGET_ENDIAN").unwrap().as_str() {
-        "little" => true,
-        "big" => false,
-        e => panic!("unexpected endianness: {}", e),
-    };
     let target_has_armv8_sha3 = env::var("CARGO_CFG_TARGET_FEATURE")
         .unwrap_or("".to_string())
         .as_str()
         .split(',')
         .any(|f| f == "sha3");
 
-    let target_implementation = if target_arch == "x86_64" {
+    let target_implementation = if cfg!(feature = "force-portable") {
+        // Skip the SIMD and assembly implementations entirely, which is
+        // mostly useful for measuring what they gain
+        if target_pointer_width == "64" {
+            TargetImplementation::Plain64
+        } else {
+            TargetImplementation::Inplace32BI
+        }
+    } else if target_arch == "x86_64" {
         if target_os != "windows" {
             TargetImplementation::Optimized64
         } else {
@@ -49,8 +64,24 @@ fn main() {
             // assembler syntax version.
             TargetImplementation::Optimized64NoAsm
         }
-    } else if target_arch == "aarch64" && target_has_armv8_sha3 {
-        TargetImplementation::Armv8Asha3
+    } else if target_arch == "aarch64" {
+        if target_has_armv8_sha3 {
+            TargetImplementation::Armv8Asha3
+        } else {
+            // Optimized64 can't be used here, even without the assembly: its
+            // times2/4/8 permutations and runtime dispatch are written
+            // against x86 SSSE3/AVX2/AVX512 intrinsics and cpuid. Plain64
+            // already builds the same portable KeccakP-1600-opt64.c that
+            // Optimized64 uses for the single permutation, so it's the
+            // fastest option available without the sha3 extensions.
+            //
+            // A NEON timesN build isn't possible either, as the K12
+            // distribution vendored here only ships parallel permutations
+            // for x86 (SSSE3/AVX2/AVX512) and none for NEON. Using one would
+            // mean vendoring and maintaining permutation code from outside
+            // of XKCP/K12, alongside its own timesN dispatch.
+            TargetImplementation::Plain64
+        }
     } else if target_pointer_width == "64" {
         TargetImplementation::Plain64
     } else if target_pointer_width == "32" {
@@ -58,6 +89,15 @@ fn main() {
     } else {
         panic!("unsupported target pointer width: {}", target_pointer_width);
     };
+    println!(
+        "cargo:rustc-env=MARSUPIAL_SYS_IMPLEMENTATION={}",
+        target_implementation.name()
+    );
+
+    println!(
+        "cargo:rustc-env=MARSUPIAL_SYS_XKCP_VERSION={}",
+        xkcp_version()
+    );
 
     let bindings = bindgen::Builder::default()
         .header("src/XKCP-K12/lib/KangarooTwelve.h")
@@ -74,6 +114,7 @@ fn main() {
             ),
             format!("-m{target_pointer_width}"),
         ])
+        .use_core()
         .parse_callbacks(Box::new(bindgen::CargoCallbacks::new()))
         .parse_callbacks(Box::new(ParseDoxygen))
         .generate()
@@ -84,6 +125,94 @@ fn main() {
         .write_to_file(out_path.join("bindings.rs"))
         .expect("Unable to write the C bindings to a file");
 
+    #[cfg(feature = "system-libk12")]
+    link_system_libk12();
+
+    #[cfg(not(feature = "system-libk12"))]
+    compile_vendored(&target_implementation, &target_os);
+}
+
+/// The revision of XKCP/K12 being built against. For the vendored sources,
+/// this is the commit the submodule is checked out at, which requires git to
+/// be available. When that can't be determined, it is `"unknown"`
+fn xkcp_version() -> String {
+    if cfg!(feature = "system-libk12") {
+        return "system".to_string();
+    }
+
+    submodule_revision().unwrap_or_else(|| "unknown".to_string())
+}
+
+/// The commit the XKCP/K12 submodule is checked out at, if it is a checkout
+/// of its own. Without that check, git would report the revision of whichever
+/// repository encloses it (such as when the crate is built from a source
+/// tarball unpacked inside another checkout)
+fn submodule_revision() -> Option<String> {
+    let submodule = PathBuf::from("src/XKCP-K12").canonicalize().ok()?;
+    let toplevel = PathBuf::from(git(&["rev-parse", "--show-toplevel"])?)
+        .canonicalize()
+        .ok()?;
+    if toplevel != submodule {
+        return None;
+    }
+
+    // The submodule's HEAD lives in its git directory, which is usually
+    // `.git/modules/...` in the superproject rather than `src/XKCP-K12/.git`
+    println!("cargo:rerun-if-changed=src/XKCP-K12/.git");
+    if let Some(git_dir) = git(&["rev-parse", "--absolute-git-dir"]) {
+        println!("cargo:rerun-if-changed={git_dir}/HEAD");
+    }
+
+    git(&["rev-parse", "HEAD"])
+}
+
+/// Run git in the XKCP/K12 submodule, returning its trimmed output if it
+/// succeeded
+fn git(args: &[&str]) -> Option<String> {
+    Command::new("git")
+        .args(["-C", "src/XKCP-K12"])
+        .args(args)
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .map(|output| output.trim().to_string())
+        .filter(|output| !output.is_empty())
+}
+
+/// Link against a libk12 provided by the system instead of compiling the
+/// vendored sources. If `K12_LIB_DIR` is set, the library is looked for in
+/// that directory. Otherwise, it is located through pkg-config
+#[cfg(feature = "system-libk12")]
+fn link_system_libk12() {
+    println!("cargo:rustc-env=MARSUPIAL_SYS_CFLAGS=");
+    println!("cargo:rerun-if-env-changed=K12_LIB_DIR");
+    match env::var_os("K12_LIB_DIR") {
+        Some(lib_dir) => {
+            println!(
+                "cargo:rustc-link-search=native={}",
+                PathBuf::from(lib_dir).display()
+            );
+            println!("cargo:rustc-link-lib=k12");
+        }
+        None => {
+            pkg_config::Config::new()
+                .probe("k12")
+                .expect("Unable to locate libk12 through pkg-config, try setting K12_LIB_DIR");
+        }
+    }
+}
+
+/// Compile the vendored XKCP/K12 sources for the selected implementation
+#[cfg(not(feature = "system-libk12"))]
+fn compile_vendored(target_implementation: &TargetImplementation, target_os: &str) {
+    let target_env = env::var("CARGO_CFG_TARGET_ENV").unwrap(); // e.g. "msvc" on Windows
+    let target_is_little_endian = match env::var("CARGO_CFG_TARGET_ENDIAN").unwrap().as_str() {
+        "little" => true,
+        "big" => false,
+        e => panic!("unexpected endianness: {}", e),
+    };
+
     // Configure the base_build, which might be used for multiple different
     // compilation steps below.
     let mut base_build = cc::Build::new();
@@ -98,7 +227,7 @@ fn main() {
     } else {
         base_build.define("BIG_ENDIAN", "1");
     }
-    match &target_implementation {
+    match target_implementation {
         TargetImplementation::Optimized64 | TargetImplementation::Optimized64NoAsm => {
             // These two targets share headers.
             base_build.include("src/XKCP-K12/lib/Optimized64");
@@ -113,21 +242,58 @@ fn main() {
             base_build.include("src/XKCP-K12/lib/ARMv8Asha3");
         }
     }
-    if let TargetImplementation::Optimized64NoAsm = &target_implementation {
+    if let TargetImplementation::Optimized64NoAsm = target_implementation {
         // Since Optimized64 and Optimized64NoAsm use the same header file,
         // KeccakP-1600-runtimeDispatch.c relies on this preprocessor var to
         // distinguish them.
         base_build.define("KeccakP1600_noAssembly", "1");
     }
+    if cfg!(feature = "reproducible") {
+        // cc otherwise derives these from the cargo profile, which can be
+        // overridden per package and through the environment
+        base_build.opt_level(2);
+        base_build.debug(false);
+
+        // cc appends flags from these variables to every compilation, so they
+        // would make the objects depend on the environment of the build
+        let target = env::var("TARGET").unwrap();
+        for var in [
+            "CFLAGS".to_string(),
+            "TARGET_CFLAGS".to_string(),
+            format!("CFLAGS_{target}"),
+            format!("CFLAGS_{}", target.replace('-', "_")),
+        ] {
+            println!("cargo:rerun-if-env-changed={var}");
+            if env::var_os(&var).is_some() {
+                panic!("{var} is set, which the reproducible feature doesn't allow");
+            }
+        }
+    }
     let base_build = base_build; // immutable from here on
 
+    let flags = base_build
+        .get_compiler()
+        .args()
+        .iter()
+        .map(|arg| arg.to_string_lossy())
+        .collect::<Vec<_>>()
+        .join(" ");
+    println!("cargo:rustc-env=MARSUPIAL_SYS_CFLAGS={flags}");
+
     let mut portable_build = base_build.clone();
     portable_build.file("src/XKCP-K12/lib/KangarooTwelve.c");
     // The different targets add additional portable files.
 
-    match &target_implementation {
+    match target_implementation {
         TargetImplementation::Optimized64 | TargetImplementation::Optimized64NoAsm => {
             portable_build.file("src/XKCP-K12/lib/Optimized64/KeccakP-1600-opt64.c");
+            // The runtime dispatch runs cpuid once per process, the first time
+            // one of the KeccakP1600timesN_IsAvailable functions is called,
+            // and caches the result in a static. Later calls only read the
+            // cached flags, and those calls happen once per update of at
+            // least two chunks anyway, so messages shorter than that never
+            // reach the dispatch at all. There's nothing for the Rust side to
+            // cache on top of it.
             portable_build.file("src/XKCP-K12/lib/Optimized64/KeccakP-1600-runtimeDispatch.c");
 
             let mut ssse3_build = base_build.clone();
@@ -156,7 +322,7 @@ fn main() {
             avx512_build.file("src/XKCP-K12/lib/Optimized64/KeccakP-1600-timesN-AVX512.c");
             // For the non-asm build we add another file below.
 
-            if let TargetImplementation::Optimized64 = &target_implementation {
+            if let TargetImplementation::Optimized64 = target_implementation {
                 let mut asm_build = base_build.clone();
                 asm_build.file("src/XKCP-K12/lib/Optimized64/KeccakP-1600-AVX2.s");
                 asm_build.file("src/XKCP-K12/lib/Optimized64/KeccakP-1600-AVX512.s");
@@ -1,5 +1,22 @@
 #![allow(non_camel_case_types)]
 #![allow(non_snake_case)]
 #![allow(non_upper_case_globals)]
+#![no_std]
+
+/// The name of the XKCP/K12 implementation selected for the target, such as
+/// `"Optimized64"` or `"Plain64"`
+pub const IMPLEMENTATION: &str = env!("MARSUPIAL_SYS_IMPLEMENTATION");
+
+/// The revision of XKCP/K12 that was built against. This is the commit hash
+/// of the vendored sources, `"system"` when linking against a system-provided
+/// libk12, or `"unknown"` if the commit couldn't be determined at build time
+/// (e.g. when git isn't available)
+pub const XKCP_VERSION: &str = env!("MARSUPIAL_SYS_XKCP_VERSION");
+
+/// The flags the vendored XKCP/K12 sources were compiled with, separated by
+/// spaces. These are the flags shared by every file, which the SIMD and
+/// assembly files add their instruction set flags to. This is empty when
+/// linking against a system-provided libk12
+pub const CFLAGS: &str = env!("MARSUPIAL_SYS_CFLAGS");
 
 include!(concat!(env!("OUT_DIR"), "/bindings.rs"));